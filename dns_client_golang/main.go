@@ -54,10 +54,10 @@ func fetchSpec(domain string, runs, runsPerSecond int, outFile string, evalFlag,
 func registerAssets(domain, outFile string, rps, totalRuns int) {
 	connector := substrate.NewSubstrateConnector(true)
 	resultsChan := make(chan string, 1000)
-	nonce := connector.RegisterAsset(domain, fmt.Sprintf("asset%d", -1), 0, resultsChan)
+	nonce := connector.RegisterAsset(domain, -1, 0, resultsChan)
 	fmt.Printf("Initial nonce: %d\n", nonce)
 	eval.RunFuncPerSecond(func(currentRun int, wg *sync.WaitGroup) {
-		connector.RegisterAsset(domain, fmt.Sprintf("asset%d", currentRun), nonce+uint32(currentRun)+1, resultsChan)
+		connector.RegisterAsset(domain, currentRun, nonce+uint32(currentRun)+1, resultsChan)
 		wg.Done()
 	}, totalRuns, rps)
 
@@ -73,10 +73,10 @@ func registerAssets(domain, outFile string, rps, totalRuns int) {
 	eval.WriteToCSV(outFile, resultArr)
 }
 
-func listenToEvents(totalRuns int, outFile string, useCache bool) {
+func listenToEvents(domain string, totalRuns int, outFile string, useCache bool) {
 	connector := substrate.NewSubstrateConnector(useCache)
 	resultsChan := make(chan string, 1000)
-	go connector.ListenForEvents(resultsChan, true, 1000)
+	go connector.ListenForEvents(domain, resultsChan, true, 1000)
 
 	var resultArr []*eval.EvalResult
 
@@ -116,7 +116,7 @@ func main() {
 	if assetEval {
 		registerAssets(domain, outFile, runsPerSecond, runs)
 	} else if listen {
-		listenToEvents(runs, outFile, useCache)
+		listenToEvents(domain, runs, outFile, useCache)
 	} else {
 		fetchSpec(domain, runs, runsPerSecond, outFile, eval, useCache)
 	}
@@ -1,15 +1,18 @@
 package substrate
 
 import (
+	"bytes"
 	"fmt"
 	"math/big"
 	"strings"
 	"sync"
 
 	gsrpc "github.com/centrifuge/go-substrate-rpc-client/v4"
+	"github.com/centrifuge/go-substrate-rpc-client/v4/scale"
 	"github.com/centrifuge/go-substrate-rpc-client/v4/signature"
 	"github.com/centrifuge/go-substrate-rpc-client/v4/types"
 	"github.com/centrifuge/go-substrate-rpc-client/v4/types/codec"
+	"golang.org/x/crypto/blake2b"
 )
 
 type DomainRes struct {
@@ -23,6 +26,62 @@ type TldRes struct {
 	ChainSpec types.Text
 }
 
+// AssetId is a pallet_assets asset on the target chain of a domain, encoded like the AssetId of
+// pallet_assetdiscovery with no XCM location.
+type AssetId struct {
+	Domain  []byte
+	LocalId []byte
+}
+
+func NewPalletAssetId(domain string, id uint32) (AssetId, error) {
+	localId, err := codec.Encode(types.NewU32(id))
+	if err != nil {
+		return AssetId{}, err
+	}
+	return AssetId{Domain: []byte(domain), LocalId: localId}, nil
+}
+
+func (a AssetId) encodeIdentity(encoder scale.Encoder) error {
+	// ChainId::Domain
+	if err := encoder.PushByte(0); err != nil {
+		return err
+	}
+	if err := encoder.Encode(a.Domain); err != nil {
+		return err
+	}
+	// AssetStandard::PalletAssets
+	if err := encoder.PushByte(0); err != nil {
+		return err
+	}
+	return encoder.Encode(a.LocalId)
+}
+
+func (a AssetId) Encode(encoder scale.Encoder) error {
+	if err := a.encodeIdentity(encoder); err != nil {
+		return err
+	}
+	// No XCM location
+	return encoder.PushByte(0)
+}
+
+// CanonicalHash is the hash the chain indexes the asset by, which leaves the location out.
+func (a AssetId) CanonicalHash() ([32]byte, error) {
+	var buf bytes.Buffer
+	buf.WriteString("assetdiscovery:asset")
+	if err := a.encodeIdentity(*scale.NewEncoder(&buf)); err != nil {
+		return [32]byte{}, err
+	}
+	return blake2b.Sum256(buf.Bytes()), nil
+}
+
+// noAuthorization encodes an absent owner authorization, so the signer must own or manage the
+// domain itself.
+type noAuthorization struct{}
+
+func (noAuthorization) Encode(encoder scale.Encoder) error {
+	return encoder.PushByte(0)
+}
+
 type SubstrateConnector struct {
 	rootSpecUrl       string
 	apiCache          map[string]*gsrpc.SubstrateAPI
@@ -141,7 +200,7 @@ func (c *SubstrateConnector) getSubstrateApi(spec ChainSpecRes, bootNodeIndex in
 	return api, nil
 }
 
-func (c *SubstrateConnector) RegisterAsset(domain, assetName string, nonce uint32, results chan string) uint32 {
+func (c *SubstrateConnector) RegisterAsset(domain string, assetIdx int, nonce uint32, results chan string) uint32 {
 
 	var (
 		rootSpec *ChainSpecRes
@@ -186,7 +245,12 @@ func (c *SubstrateConnector) RegisterAsset(domain, assetName string, nonce uint3
 
 	// fmt.Printf("Meta: %#v\n", meta)
 
-	call, err := types.NewCall(meta, registerAssetTx, domain, assetName)
+	assetId, err := NewPalletAssetId(domain, uint32(assetIdx+1))
+	if err != nil {
+		panic(err)
+	}
+
+	call, err := types.NewCall(meta, registerAssetTx, []byte(domain), assetId, noAuthorization{})
 	if err != nil {
 		panic(err)
 	}
@@ -254,10 +318,10 @@ func (c *SubstrateConnector) RegisterAsset(domain, assetName string, nonce uint3
 			}
 			startBlock := block.Block.Header.Number
 			startBlockNumber = types.NewU256(*new(big.Int).SetUint64(uint64(startBlock)))
-			if assetName == "asset-1" {
+			if assetIdx == -1 {
 				break
 			}
-			res := fmt.Sprintf("%s,%s", assetName, startBlockNumber)
+			res := fmt.Sprintf("%d,%s", assetIdx, startBlockNumber)
 			results <- res
 			fmt.Printf("Completed at block number: %d\n", startBlockNumber)
 			break
@@ -271,7 +335,7 @@ func (c *SubstrateConnector) RegisterAsset(domain, assetName string, nonce uint3
 	return nonce
 }
 
-func (c *SubstrateConnector) ListenForEvents(results chan string, assetEval bool, totalRuns int) {
+func (c *SubstrateConnector) ListenForEvents(domain string, results chan string, assetEval bool, totalRuns int) {
 	var (
 		rootSpec *ChainSpecRes
 		err      error
@@ -319,7 +383,7 @@ func (c *SubstrateConnector) ListenForEvents(results chan string, assetEval bool
 
 	type EventAssetRegisteredForDomain struct {
 		Phase        types.Phase
-		AssetHash    string
+		AssetHash    []byte
 		Domain       string
 		CurrentBlock types.U256
 		Topics       []types.Hash
@@ -344,6 +408,20 @@ func (c *SubstrateConnector) ListenForEvents(results chan string, assetEval bool
 		AssetDiscoveryModule_AssetProviderRevoked      []EventAssetProviderRevoked
 	}
 
+	// Registered assets are only known by their hash, map them back to the run that registered them
+	assetIdxs := make(map[string]int)
+	for i := -1; i < totalRuns; i++ {
+		assetId, err := NewPalletAssetId(domain, uint32(i+1))
+		if err != nil {
+			panic(err)
+		}
+		hash, err := assetId.CanonicalHash()
+		if err != nil {
+			panic(err)
+		}
+		assetIdxs[string(hash[:])] = i
+	}
+
 	key, err := types.CreateStorageKey(meta, "System", "Events", nil)
 
 	if err != nil {
@@ -379,10 +457,11 @@ func (c *SubstrateConnector) ListenForEvents(results chan string, assetEval bool
 				fmt.Printf("\tAsset Registered %v\n\n", e)
 				if assetEval {
 					blockNumber := e.CurrentBlock
-					if e.AssetHash == "asset-1" {
+					assetIdx, ok := assetIdxs[string(e.AssetHash)]
+					if !ok || assetIdx == -1 {
 						continue
 					}
-					res := fmt.Sprintf("%s,%s", e.AssetHash[5:], blockNumber.Int)
+					res := fmt.Sprintf("%d,%s", assetIdx, blockNumber.Int)
 					results <- res
 				}
 			}
@@ -1,9 +1,11 @@
-use node_template_runtime::{AccountId, RuntimeGenesisConfig, Signature, WASM_BINARY};
+use node_template_runtime::{
+	AccountId, RuntimeGenesisConfig, Signature, TreasuryAccount, EXISTENTIAL_DEPOSIT, WASM_BINARY,
+};
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
 use sp_core::{sr25519, Pair, Public};
-use sp_runtime::traits::{IdentifyAccount, Verify};
+use sp_runtime::traits::{Get, IdentifyAccount, Verify};
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
@@ -101,7 +103,13 @@ fn testnet_genesis(
 	serde_json::json!({
 		"balances": {
 			// Configure endowed accounts with initial balance of 1 << 60.
-			"balances": endowed_accounts.iter().cloned().map(|k| (k, 1u64 << 60)).collect::<Vec<_>>(),
+			"balances": endowed_accounts
+				.iter()
+				.cloned()
+				.map(|k| (k, 1u64 << 60))
+				// The treasury receives slashed deposits smaller than the existential deposit.
+				.chain(std::iter::once((TreasuryAccount::get(), EXISTENTIAL_DEPOSIT as u64)))
+				.collect::<Vec<_>>(),
 		},
 		"aura": {
 			"authorities": initial_authorities.iter().map(|x| (x.0.clone())).collect::<Vec<_>>(),
@@ -113,5 +121,12 @@ fn testnet_genesis(
 			// Assign network admin rights.
 			"key": Some(root_key),
 		},
+		"assetDiscoveryModule": {
+			// The validators also run the offchain workers verifying assets.
+			"authorities": initial_authorities
+				.iter()
+				.map(|x| AccountPublic::from(sr25519::Public::from(x.0.clone())).into_account())
+				.collect::<Vec<_>>(),
+		},
 	})
 }
@@ -0,0 +1,156 @@
+//! RPC interface for the asset discovery pallet.
+//!
+//! Exposes the [`AssetDiscoveryRuntimeApi`] under the `assetDiscovery_*` namespace.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use serde::Serialize;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{ErrorObject, ErrorObjectOwned},
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::traits::Block as BlockT;
+
+pub use pallet_assetdiscovery_runtime_api::{
+	AssetDiscoveryApi as AssetDiscoveryRuntimeApi, AssetInfo,
+};
+
+/// Largest page a single search call returns.
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Error code returned when the runtime API call fails.
+const RUNTIME_ERROR: i32 = 1;
+
+/// Matching `(asset, domain, description)` entries and the pair to resume the search after.
+pub type SearchPage<AccountId, BlockNumber> =
+	(Vec<(Bytes, Bytes, AssetInfo<AccountId, BlockNumber>)>, Option<(Bytes, Bytes)>);
+
+#[rpc(client, server)]
+pub trait AssetDiscoveryApi<BlockHash, AccountId, BlockNumber> {
+	/// Lists the domains providing an asset, by canonical hash.
+	#[method(name = "assetDiscovery_providersForAsset")]
+	fn providers_for_asset(&self, asset_hash: Bytes, at: Option<BlockHash>)
+		-> RpcResult<Vec<Bytes>>;
+
+	/// Lists the canonical hashes of the assets provided by a domain.
+	#[method(name = "assetDiscovery_assetsForDomain")]
+	fn assets_for_domain(&self, domain: Bytes, at: Option<BlockHash>) -> RpcResult<Vec<Bytes>>;
+
+	/// Returns the description of an asset on the target chain of one of its providers.
+	#[method(name = "assetDiscovery_assetMetadata")]
+	fn asset_metadata(
+		&self,
+		asset_hash: Bytes,
+		domain: Bytes,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<AssetInfo<AccountId, BlockNumber>>>;
+
+	/// Lists the `(asset, domain)` pairs whose asset name starts with the given prefix, starting
+	/// after the given pair, along with the pair to pass to the next call to continue the search.
+	/// The search is over when no pair is returned, even if the page was not full.
+	#[method(name = "assetDiscovery_searchAssets")]
+	fn search_assets(
+		&self,
+		name_prefix: Bytes,
+		start_after: Option<(Bytes, Bytes)>,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<SearchPage<AccountId, BlockNumber>>;
+}
+
+/// Provides RPC methods to search assets and their providers.
+pub struct AssetDiscovery<C, Block> {
+	client: Arc<C>,
+	_marker: PhantomData<Block>,
+}
+
+impl<C, Block> AssetDiscovery<C, Block> {
+	/// Creates a new instance of the AssetDiscovery RPC helper.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+fn runtime_error(err: impl std::fmt::Debug) -> ErrorObjectOwned {
+	ErrorObject::owned(RUNTIME_ERROR, "Unable to query assets.", Some(format!("{:?}", err)))
+}
+
+fn into_bytes(entries: Vec<Vec<u8>>) -> Vec<Bytes> {
+	entries.into_iter().map(Bytes::from).collect()
+}
+
+impl<C, Block, AccountId, BlockNumber>
+	AssetDiscoveryApiServer<<Block as BlockT>::Hash, AccountId, BlockNumber>
+	for AssetDiscovery<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: AssetDiscoveryRuntimeApi<Block, AccountId, BlockNumber>,
+	AccountId: Codec + Serialize + Send + Sync + 'static,
+	BlockNumber: Codec + Serialize + Send + Sync + 'static,
+{
+	fn providers_for_asset(
+		&self,
+		asset_hash: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<Bytes>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.providers_for_asset(at, asset_hash.to_vec()).map(into_bytes).map_err(runtime_error)
+	}
+
+	fn assets_for_domain(
+		&self,
+		domain: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<Bytes>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.assets_for_domain(at, domain.to_vec()).map(into_bytes).map_err(runtime_error)
+	}
+
+	fn asset_metadata(
+		&self,
+		asset_hash: Bytes,
+		domain: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<AssetInfo<AccountId, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.asset_metadata(at, asset_hash.to_vec(), domain.to_vec()).map_err(runtime_error)
+	}
+
+	fn search_assets(
+		&self,
+		name_prefix: Bytes,
+		start_after: Option<(Bytes, Bytes)>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<SearchPage<AccountId, BlockNumber>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.search_assets(
+			at,
+			name_prefix.to_vec(),
+			start_after.map(|(asset, domain)| (asset.to_vec(), domain.to_vec())),
+			limit.min(MAX_PAGE_SIZE),
+		)
+		.map(|(entries, next)| {
+			let entries = entries
+				.into_iter()
+				.map(|(asset, domain, info)| (asset.into(), domain.into(), info))
+				.collect();
+			(entries, next.map(|(asset, domain)| (asset.into(), domain.into())))
+		})
+		.map_err(runtime_error)
+	}
+}
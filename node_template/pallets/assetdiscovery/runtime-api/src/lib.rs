@@ -0,0 +1,42 @@
+//! Runtime API definition for the asset discovery pallet.
+//!
+//! Lets clients find the providers of an asset and search assets by name without reading the
+//! pallet storage directly.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_assetdiscovery::AssetInfo;
+
+sp_api::decl_runtime_apis! {
+	pub trait AssetDiscoveryApi<AccountId, BlockNumber>
+	where
+		AccountId: Codec,
+		BlockNumber: Codec,
+	{
+		/// Domains verified to provide the asset with the given canonical hash.
+		fn providers_for_asset(asset_hash: Vec<u8>) -> Vec<Vec<u8>>;
+
+		/// Canonical hashes of the assets provided by `domain`.
+		fn assets_for_domain(domain: Vec<u8>) -> Vec<Vec<u8>>;
+
+		/// Description of an asset on the target chain of one of its providers.
+		fn asset_metadata(asset_hash: Vec<u8>, domain: Vec<u8>) -> Option<AssetInfo<AccountId, BlockNumber>>;
+
+		/// Described `(asset, domain)` pairs whose asset name starts with `name_prefix`, ordered
+		/// by storage key, starting after `start_after`.
+		///
+		/// Each call scans a bounded number of descriptions and also returns the pair to resume
+		/// after, or `None` once all of them were scanned.
+		fn search_assets(
+			name_prefix: Vec<u8>,
+			start_after: Option<(Vec<u8>, Vec<u8>)>,
+			limit: u32,
+		) -> (
+			Vec<(Vec<u8>, Vec<u8>, AssetInfo<AccountId, BlockNumber>)>,
+			Option<(Vec<u8>, Vec<u8>)>,
+		);
+	}
+}
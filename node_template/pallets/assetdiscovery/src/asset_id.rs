@@ -0,0 +1,162 @@
+//! Canonical identifiers of the assets provided by domains.
+//!
+//! An asset is identified by the chain it lives on, the standard it implements and its id local to
+//! that standard, so that providers of the same asset register the same identifier. Its canonical
+//! hash is what the pallet indexes assets by.
+
+use codec::{Decode, Encode};
+use frame_support::RuntimeDebug;
+use scale_info::{prelude::vec::Vec, TypeInfo};
+use sp_core::H256;
+use xcm::{latest::Location, VersionedLocation};
+
+/// Maximum length of the local id of an asset, in bytes.
+pub const MAX_LOCAL_ID_LEN: usize = 64;
+/// Length of an EVM contract address.
+const EVM_ADDRESS_LEN: usize = 20;
+
+/// The chain an asset lives on.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum ChainId {
+	/// A chain registered in BCDNS, by its domain name
+	Domain(Vec<u8>),
+	/// Any chain, by the hash of its genesis block
+	Genesis(H256),
+}
+
+/// The standard an asset implements, which gives the meaning of its local id.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum AssetStandard {
+	/// An asset of `pallet_assets`, identified by its SCALE encoded asset id
+	PalletAssets,
+	/// An ERC-20 token, identified by its contract address
+	Erc20,
+	/// A collection of non fungible tokens, identified by its collection id
+	NftCollection,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum AssetIdError {
+	/// The chain domain is empty or has no TLD
+	InvalidChain,
+	/// The local id is empty, too long or does not fit the standard
+	InvalidLocalId,
+	/// The XCM location cannot be converted to the current XCM version
+	InvalidLocation,
+}
+
+/// Structured identifier of an asset.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct AssetId {
+	pub chain: ChainId,
+	pub standard: AssetStandard,
+	pub local_id: Vec<u8>,
+	// How other chains reach the asset over XCM, if it is reachable
+	pub location: Option<VersionedLocation>,
+}
+
+impl AssetId {
+	/// Canonical hash of the asset.
+	///
+	/// The XCM location only describes how to reach the asset, so it is left out and providers
+	/// giving different locations for the same asset still share its hash.
+	pub fn canonical_hash(&self) -> H256 {
+		let identity = (b"assetdiscovery:asset", &self.chain, self.standard, &self.local_id);
+		H256(sp_core::hashing::blake2_256(&identity.encode()))
+	}
+
+	/// Checks that the identifier is well formed.
+	pub fn validate(&self) -> Result<(), AssetIdError> {
+		match &self.chain {
+			ChainId::Domain(domain) if !domain.contains(&b'.') || domain.ends_with(b".") =>
+				return Err(AssetIdError::InvalidChain),
+			ChainId::Domain(_) | ChainId::Genesis(_) => {},
+		}
+
+		let local_id_valid = match self.standard {
+			AssetStandard::Erc20 => self.local_id.len() == EVM_ADDRESS_LEN,
+			AssetStandard::PalletAssets | AssetStandard::NftCollection =>
+				!self.local_id.is_empty() && self.local_id.len() <= MAX_LOCAL_ID_LEN,
+		};
+		if !local_id_valid {
+			return Err(AssetIdError::InvalidLocalId);
+		}
+
+		if let Some(location) = &self.location {
+			Location::try_from(location.clone()).map_err(|_| AssetIdError::InvalidLocation)?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn asset(standard: AssetStandard, local_id: &[u8]) -> AssetId {
+		AssetId {
+			chain: ChainId::Domain(b"chain.tld".to_vec()),
+			standard,
+			local_id: local_id.to_vec(),
+			location: None,
+		}
+	}
+
+	#[test]
+	fn encoding_round_trips() {
+		let mut asset = asset(AssetStandard::PalletAssets, &42u32.encode());
+		asset.location = Some(Location::parent().into());
+		assert_eq!(AssetId::decode(&mut &asset.encode()[..]).unwrap(), asset);
+
+		let asset = AssetId {
+			chain: ChainId::Genesis(H256::repeat_byte(7)),
+			standard: AssetStandard::Erc20,
+			local_id: [1u8; EVM_ADDRESS_LEN].to_vec(),
+			location: None,
+		};
+		assert_eq!(AssetId::decode(&mut &asset.encode()[..]).unwrap(), asset);
+	}
+
+	#[test]
+	fn canonical_hash_ignores_the_location() {
+		let plain = asset(AssetStandard::PalletAssets, &42u32.encode());
+		let mut located = plain.clone();
+		located.location = Some(Location::parent().into());
+		assert_eq!(plain.canonical_hash(), located.canonical_hash());
+
+		let other_standard = asset(AssetStandard::NftCollection, &42u32.encode());
+		assert_ne!(plain.canonical_hash(), other_standard.canonical_hash());
+		let other_id = asset(AssetStandard::PalletAssets, &43u32.encode());
+		assert_ne!(plain.canonical_hash(), other_id.canonical_hash());
+	}
+
+	#[test]
+	fn validates_the_chain_domain() {
+		let mut asset = asset(AssetStandard::PalletAssets, &42u32.encode());
+		assert_eq!(asset.validate(), Ok(()));
+
+		for domain in [&b"chain"[..], b"chain.tld."] {
+			asset.chain = ChainId::Domain(domain.to_vec());
+			assert_eq!(asset.validate(), Err(AssetIdError::InvalidChain));
+		}
+	}
+
+	#[test]
+	fn validates_the_local_id_against_the_standard() {
+		assert_eq!(asset(AssetStandard::Erc20, &[1; EVM_ADDRESS_LEN]).validate(), Ok(()));
+		assert_eq!(
+			asset(AssetStandard::Erc20, &[1; EVM_ADDRESS_LEN + 1]).validate(),
+			Err(AssetIdError::InvalidLocalId)
+		);
+		assert_eq!(asset(AssetStandard::NftCollection, &[1; MAX_LOCAL_ID_LEN]).validate(), Ok(()));
+		assert_eq!(
+			asset(AssetStandard::NftCollection, &[1; MAX_LOCAL_ID_LEN + 1]).validate(),
+			Err(AssetIdError::InvalidLocalId)
+		);
+		assert_eq!(
+			asset(AssetStandard::PalletAssets, &[]).validate(),
+			Err(AssetIdError::InvalidLocalId)
+		);
+	}
+}
@@ -0,0 +1,16 @@
+use super::KEY_TYPE;
+use sp_runtime::{
+	app_crypto::{app_crypto, sr25519},
+	MultiSignature, MultiSigner,
+};
+app_crypto!(sr25519, KEY_TYPE);
+
+/// Key of an offchain worker authority allowed to submit verifications.
+pub struct AuthorityId;
+
+// implemented for runtime
+impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for AuthorityId {
+	type RuntimeAppPublic = Public;
+	type GenericSignature = sp_core::sr25519::Signature;
+	type GenericPublic = sp_core::sr25519::Public;
+}
@@ -8,8 +8,14 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod asset_id;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod crypto;
+pub mod metadata;
+pub mod migrations;
+pub mod multiaddr;
+pub mod remote;
 pub mod weights;
 
 pub use weights::*;
@@ -18,18 +24,42 @@ pub use weights::*;
 pub mod pallet {
 
 	use super::*;
-	use codec::Encode;
+	use crate::{
+		asset_id::{AssetId, AssetStandard, ChainId},
+		metadata::DomainMapLayout,
+		remote::{self, ProvenState, ReadProof},
+	};
+	use codec::{DecodeAll, Encode};
 	use core::str;
-	use frame_support::{pallet_prelude::*, Deserialize, Serialize};
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{Currency, Imbalance, ReservableCurrency},
+		Deserialize, Serialize,
+	};
 	use frame_system::{
-		offchain::{CreateSignedTransaction, SubmitTransaction},
+		offchain::{
+			AppCrypto, CreateSignedTransaction, SendUnsignedTransaction, SignedPayload, Signer,
+			SigningTypes,
+		},
 		pallet_prelude::{BlockNumberFor, *},
 	};
-	use scale_info::prelude::{boxed::Box, format, string::String, vec, vec::Vec};
-	use sp_core::{offchain::Duration, U256};
-	use sp_runtime::offchain::http;
+	use scale_info::prelude::{boxed::Box, string::String, vec, vec::Vec};
+	use sp_core::{
+		offchain::{Duration, Timestamp},
+		storage::well_known_keys,
+		H256, U256,
+	};
+	use sp_runtime::{
+		offchain::{http, storage::StorageValueRef, KeyTypeId},
+		traits::{Header as HeaderT, IdentifyAccount, One, Saturating, Verify, Zero},
+		Perbill, SaturatedConversion,
+	};
+
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -40,58 +70,661 @@ pub mod pallet {
 		/// A type representing the weights required by the dispatchables of this pallet.
 		type WeightInfo: WeightInfo;
 		type PalletRootDNS: pallet_rootdns::Config;
+		type PalletLightClient: pallet_lightclient::Config;
+		/// Key type of the offchain worker authorities signing the submitted verifications.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+		/// Origin allowed to change the set of offchain worker authorities.
+		type AuthorityOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Origin allowed to override the parameters below through [`Parameters`].
+		type ParametersOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Number of domains revalidated, and of requests processed, per offchain worker run.
+		#[pallet::constant]
+		type DomainBatchSize: Get<u32>;
+		/// Number of blocks a request can wait for verification before it expires.
+		#[pallet::constant]
+		type RequestLifetime: Get<u32>;
+		/// Domains are revalidated every `OcwCadence` blocks.
+		#[pallet::constant]
+		type OcwCadence: Get<u32>;
+		/// Time given to each remote node to answer, in milliseconds.
+		#[pallet::constant]
+		type HttpTimeoutMs: Get<u64>;
+		/// Number of blocks within which every provider is revalidated.
+		#[pallet::constant]
+		type RevalidationWindow: Get<u32>;
+		/// Number of offchain worker authorities that must attest the same outcome before a
+		/// provider is accepted or removed, capped at the number of authorities.
+		#[pallet::constant]
+		type AttestationThreshold: Get<u32>;
+		/// Number of blocks after which an attestation that did not reach the threshold is
+		/// discarded.
+		#[pallet::constant]
+		type AttestationWindow: Get<u32>;
+		/// Maximum number of offchain worker authorities.
+		#[pallet::constant]
+		type MaxAuthorities: Get<u32>;
+		/// Name of the `pallet_tld` instance in the runtime of the TLD chains.
+		#[pallet::constant]
+		type TldModulePrefix: Get<Vec<u8>>;
+		/// Name of the storage map holding the domains in `pallet_tld`.
+		#[pallet::constant]
+		type TldStoragePrefix: Get<Vec<u8>>;
+		/// Name of the `pallet_assets` instance in the runtime of the target chains.
+		#[pallet::constant]
+		type AssetsModulePrefix: Get<Vec<u8>>;
+		/// Number of TLD nodes that must agree before a domain counts as valid or revoked.
+		#[pallet::constant]
+		type TldQueryQuorum: Get<u32>;
+		/// Number of blocks a proof from a TLD chain may lag behind the best header finalized by
+		/// its light client.
+		#[pallet::constant]
+		type MaxProofAge: Get<u32>;
+		/// Signature used by domain owners and managers to authorize asset registrations.
+		type OwnerSignature: Verify<Signer = Self::OwnerPublic> + Parameter;
+		/// Public key matching `OwnerSignature`, convertible into an account of the TLD chains.
+		type OwnerPublic: IdentifyAccount<AccountId = Self::AccountId>;
+		/// The currency used to reserve request deposits.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// The amount reserved from the requester of a registration or withdrawal until it is
+		/// verified.
+		#[pallet::constant]
+		type RequestDeposit: Get<BalanceOf<Self>>;
+		/// Share of the deposit slashed when a request is rejected or expires.
+		#[pallet::constant]
+		type RequestSlash: Get<Perbill>;
+		/// Number of requests and withdrawals an account can have pending at once.
+		#[pallet::constant]
+		type MaxPendingRequests: Get<u32>;
+		/// The amount reserved from the account tracking the asset manifest of a domain until the
+		/// domain stops being tracked.
+		#[pallet::constant]
+		type ManifestDeposit: Get<BalanceOf<Self>>;
+		/// The account receiving slashed deposits.
+		type TreasuryAccount: Get<Self::AccountId>;
+	}
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"bcda");
+
+	const ASSETS_STORAGE_PREFIX: &[u8] = b"Asset";
+	const ASSETS_METADATA_PREFIX: &[u8] = b"Metadata";
+	const SYSTEM_MODULE_PREFIX: &[u8] = b"System";
+	const BLOCK_HASH_STORAGE_PREFIX: &[u8] = b"BlockHash";
+	const TLD_MANAGERS_STORAGE_PREFIX: &[u8] = b"DomainManagers";
+	const TLD_MANIFESTS_STORAGE_PREFIX: &[u8] = b"AssetManifests";
+	const TLD_TARGET_HEADERS_STORAGE_PREFIX: &[u8] = b"TargetHeaders";
+	// Prefix of the messages signed to authorize the registration of an asset for a domain
+	const AUTHORIZATION_PREFIX: &[u8] = b"assetdiscovery:authorize";
+	// Number of blocks an offchain worker submission stays valid after the block it was made at
+	const PAYLOAD_LONGEVITY: u32 = 10;
+	// Cap on the number of consecutive failures that make a provider due sooner
+	const MAX_FAILURE_WEIGHT: u32 = 4;
+	// Prefix of the offchain storage keys caching the domain map layout of each TLD chain
+	const LAYOUT_CACHE_PREFIX: &[u8] = b"assetdiscovery::layout::";
+	/// Maximum number of assets an asset manifest can list.
+	pub const MAX_MANIFEST_ENTRIES: usize = 512;
+	/// Maximum number of asset descriptions a single `search_assets` call reads.
+	pub const MAX_SEARCH_SCAN: u32 = 1_000;
+	/// Maximum number of assets a single metadata refresh describes.
+	pub const MAX_REFRESHED_ASSETS: usize = 32;
+	// Largest asset manifest file the offchain worker downloads, in bytes
+	const MAX_MANIFEST_LEN: usize = 128 * 1024;
+	// Rough cost of hashing one byte of a storage proof into its node database
+	const PROOF_BYTE_WEIGHT: u64 = 5_000;
+	// Rough cost of reading one value from the node database of a storage proof
+	const PROOF_READ_WEIGHT: u64 = 10_000_000;
+	// Rough cost of verifying one sr25519 signature
+	const SIGNATURE_WEIGHT: u64 = 50_000_000;
+
+	/// Upper bound of the execution cost of hashing `proof` and reading `reads` values from it.
+	fn proof_weight(proof: &ReadProof, reads: u64) -> u64 {
+		(proof.encoded_size() as u64)
+			.saturating_mul(PROOF_BYTE_WEIGHT)
+			.saturating_add(reads.saturating_mul(PROOF_READ_WEIGHT))
+	}
+
+	pub type RequestId = u64;
+
+	/// Revalidation state of a providing domain.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct ProviderCheck<BlockNumber> {
+		// Block the domain was last proven to be registered at
+		pub last_verified: BlockNumber,
+		// Block the domain was last revalidated at, whether or not the TLD chain could be reached
+		pub last_attempted: BlockNumber,
+		// Revalidations in a row that could not reach the TLD chain
+		pub failures: u32,
+	}
+
+	/// How a domain came to be a provider of an asset.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct ProviderInfo<BlockNumber> {
+		// Request that was verified, `None` for assets listed in the manifest of the domain
+		pub request_id: Option<RequestId>,
+		pub verified_at: BlockNumber,
+	}
+
+	/// Ingestion state of the asset manifest of a domain.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct ManifestState<BlockNumber> {
+		// Hash of the last ingested manifest, `None` until the first one is
+		pub content_hash: Option<H256>,
+		pub checked_at: BlockNumber,
+	}
+
+	/// What offchain worker authorities attest the outcome of.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub enum AttestationSubject {
+		/// Whether a request is verified or rejected
+		Request(RequestId),
+		/// Whether a providing domain is still registered on its TLD chain
+		Revocation(Vec<u8>),
+		/// Which asset manifest a domain publishes
+		Manifest(Vec<u8>),
+		/// Which layout the domain map of a TLD chain has
+		TldLayout(Vec<u8>),
+	}
+
+	/// Outcome an offchain worker authority proved about an [`AttestationSubject`].
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub enum AttestationOutcome {
+		Verified,
+		Rejected(RejectionReason),
+		Revoked,
+		Registered,
+		/// The domain publishes the manifest with the given content hash
+		Published(H256),
+		/// The domain is not registered or publishes no manifest
+		Unpublished,
+		/// The layout and the runtime code it was derived from hash to the given hash
+		Layout(H256),
+	}
+
+	/// Why a request was rejected by the offchain worker.
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub enum RejectionReason {
+		/// The domain is not registered on its TLD chain
+		DomainNotRegistered,
+		/// The target chain of the domain has no asset with the requested id
+		AssetNotFound,
+		/// The target chain of the domain is not the chain the asset lives on
+		WrongChain,
+		/// The account authorizing the request neither owns nor manages the domain
+		NotAuthorized,
+		/// The domain committed no header of its target chain to prove the asset at
+		NoTargetHeader,
 	}
 
-	const TLD_MODULE_PREFIX: &[u8] = b"TldModule";
-	const TLD_STORAGE_PREFIX: &[u8] = b"DomainMap";
-	const DOMAIN_BATCH_SIZE: usize = 10;
-	const REQUEST_LIFETIME: u32 = 1000;
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub enum RequestStatus {
+		/// Waiting for an offchain worker to pick the request up
+		Pending,
+		/// An offchain worker is checking the domain and the asset
+		Verifying,
+		/// The domain was registered as a provider of the asset
+		Verified,
+		Rejected(RejectionReason),
+		/// The request was not verified within its lifetime
+		Expired,
+	}
 
-	#[derive(Encode, Decode, Clone, PartialEq, Default, TypeInfo)]
-	pub struct ProviderList {
-		pub providers: Vec<Vec<u8>>,
+	impl RequestStatus {
+		/// Whether the request can still be verified or rejected.
+		pub fn is_open(&self) -> bool {
+			matches!(self, RequestStatus::Pending | RequestStatus::Verifying)
+		}
 	}
 
-	#[derive(Encode, Decode, Clone, PartialEq, Default, TypeInfo)]
-	pub struct AssetList {
-		pub assets: Vec<Vec<u8>>,
+	/// Consent of the owner or of a manager of a domain to register one of its assets, signed
+	/// off-chain over `(AUTHORIZATION_PREFIX, domain, asset_hash, requester, expires_at)`.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct OwnerAuthorization<AccountId, BlockNumber, Signature> {
+		pub authorizer: AccountId,
+		// Last block the authorization can be used at
+		pub expires_at: BlockNumber,
+		pub signature: Signature,
 	}
 
-	#[derive(Encode, Decode, Clone, PartialEq, Default, TypeInfo, Debug)]
-	pub struct PendingRequest {
-		pub requester: Vec<u8>,
+	/// A request to register `domain` as a provider of `asset`.
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct AssetRequest<AccountId> {
+		pub requester: AccountId,
+		// Account that must own or manage `domain` on its TLD chain
+		pub authorizer: AccountId,
 		pub domain: Vec<u8>,
+		// Canonical hash of `asset`, which the asset is indexed by
 		pub asset_hash: Vec<u8>,
-		pub timestamp: U256,
+		pub asset: AssetId,
+		// Block number after which the request expires
+		pub expires_at: U256,
+		pub status: RequestStatus,
+	}
+
+	/// A request of `requester` to withdraw `domain` from the providers of its assets.
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct Withdrawal<AccountId, BlockNumber> {
+		// Account that must own or manage `domain` on its TLD chain
+		pub requester: AccountId,
+		pub domain: Vec<u8>,
+		// Canonical hash of the withdrawn asset, `None` to withdraw all the assets of the domain
+		pub asset_hash: Option<Vec<u8>>,
+		pub expires_at: BlockNumber,
+	}
+
+	/// Balance type of the `pallet_assets` instance of the target chains.
+	pub type RemoteBalance = u128;
+
+	/// Leading fields of `pallet_assets::AssetDetails` as stored by the target chains.
+	#[derive(Decode)]
+	struct RemoteAssetDetails<AccountId> {
+		owner: AccountId,
+		_issuer: AccountId,
+		_admin: AccountId,
+		_freezer: AccountId,
+		_supply: RemoteBalance,
+		_deposit: RemoteBalance,
+		min_balance: RemoteBalance,
+	}
+
+	/// `pallet_assets::AssetMetadata` as stored by the target chains.
+	#[derive(Decode, Default)]
+	struct RemoteAssetMetadata {
+		_deposit: RemoteBalance,
+		name: Vec<u8>,
+		symbol: Vec<u8>,
+		decimals: u8,
+		_is_frozen: bool,
+	}
+
+	/// Description of an asset read from the target chain of one of its providers.
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct AssetInfo<AccountId, BlockNumber> {
+		pub name: Vec<u8>,
+		pub symbol: Vec<u8>,
+		pub decimals: u8,
+		pub min_balance: RemoteBalance,
+		pub owner: AccountId,
+		// Block at which the description was last proven
+		pub updated_at: BlockNumber,
+	}
+
+	/// Data submitted by an offchain worker authority, signed with its `public` key.
+	///
+	/// `block_number` is the block the offchain worker ran at. Payloads are only accepted for
+	/// `PAYLOAD_LONGEVITY` blocks after it, so that they cannot be replayed later.
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct OcwPayload<Public, BlockNumber, Data> {
+		pub data: Data,
+		pub block_number: BlockNumber,
+		pub public: Public,
 	}
 
+	impl<T: SigningTypes, Data: Encode> SignedPayload<T>
+		for OcwPayload<T::Public, BlockNumberFor<T>, Data>
+	{
+		fn public(&self) -> T::Public {
+			self.public.clone()
+		}
+	}
+
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct VerificationStart {
+		pub request_ids: Vec<RequestId>,
+	}
+
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct DomainVerification {
+		pub request_id: RequestId,
+		pub proof: ReadProof,
+		pub asset_proof: ReadProof,
+	}
+
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct RequestRejection {
+		pub request_id: RequestId,
+		pub proof: ReadProof,
+		// Only needed when the domain is registered but the asset is missing
+		pub asset_proof: Option<ReadProof>,
+	}
+
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct RevokedDomains {
+		pub domains: Vec<(Vec<u8>, ReadProof)>,
+		// Number of assets the domains provide, bounding the weight of deregistering them
+		pub removed_assets: u32,
+	}
+
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct AssetMetadataRefresh {
+		pub domain: Vec<u8>,
+		pub proof: ReadProof,
+		// Proofs of the assets provided by the domain, read from its target chain
+		pub assets: Vec<(Vec<u8>, ReadProof)>,
+	}
+
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct RevalidationFailures {
+		pub domains: Vec<Vec<u8>>,
+	}
+
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct WithdrawalProof {
+		pub request_id: RequestId,
+		// Proof of the domain and of whether the requester manages it, read from its TLD chain
+		pub proof: ReadProof,
+		// Number of assets withdrawn, bounding the weight of deregistering them
+		pub withdrawn_assets: u32,
+	}
+
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct ManifestIngestion {
+		pub domain: Vec<u8>,
+		// Proof of the domain and of its manifest record, read from its TLD chain
+		pub proof: ReadProof,
+		// Content of the manifest, left empty when it did not change
+		pub assets: Vec<AssetId>,
+		// Proof of the listed assets, read from the target chain of the domain
+		pub asset_proof: Option<ReadProof>,
+		// Number of assets the domain provides, bounding the weight of deregistering them
+		pub existing_assets: u32,
+	}
+
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct TldLayout {
+		pub tld: Vec<u8>,
+		pub layout: DomainMapLayout,
+		// Proof of the hash of the runtime code the layout was derived from, read from the TLD
+		// chain without the code itself
+		pub proof: ReadProof,
+	}
+
+	/// Domain map layout of a TLD chain cached by the offchain worker, along with the runtime
+	/// version it was derived from and the block it was last checked at.
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+	struct LayoutCache {
+		spec_version: u32,
+		layout: DomainMapLayout,
+		checked_at: u32,
+	}
+
+	/// Overrides of the `Config` parameters, set by `T::ParametersOrigin`.
+	///
+	/// `None` falls back on the value configured in the runtime.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug, TypeInfo)]
+	pub struct ParameterOverrides {
+		pub domain_batch_size: Option<u32>,
+		pub request_lifetime: Option<u32>,
+		pub ocw_cadence: Option<u32>,
+		pub http_timeout_ms: Option<u64>,
+		pub revalidation_window: Option<u32>,
+		pub attestation_threshold: Option<u32>,
+		pub max_pending_requests: Option<u32>,
+		pub tld_module_prefix: Option<Vec<u8>>,
+		pub tld_storage_prefix: Option<Vec<u8>>,
+		pub assets_module_prefix: Option<Vec<u8>>,
+		pub tld_query_quorum: Option<u32>,
+	}
+
+	pub type OcwPayloadOf<T, Data> =
+		OcwPayload<<T as SigningTypes>::Public, BlockNumberFor<T>, Data>;
+
+	/// Domains providing each asset, keyed by asset and domain.
 	#[pallet::storage]
 	#[pallet::getter(fn asset_providers)]
-	pub(super) type AssetProviders<T: Config> =
-		StorageMap<_, Blake2_128Concat, Vec<u8>, ProviderList, OptionQuery>;
-
+	pub(super) type AssetProviders<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		Blake2_128Concat,
+		Vec<u8>,
+		ProviderInfo<BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// Assets provided by each domain, keyed by domain and asset. Mirrors [`AssetProviders`].
 	#[pallet::storage]
 	#[pallet::getter(fn provider_assets)]
-	pub(super) type ProviderAssets<T: Config> =
-		StorageMap<_, Blake2_128Concat, Vec<u8>, AssetList, OptionQuery>;
+	pub(super) type ProviderAssets<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		Blake2_128Concat,
+		Vec<u8>,
+		ProviderInfo<BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// Number of domains providing each asset.
+	#[pallet::storage]
+	#[pallet::getter(fn provider_count)]
+	pub(super) type ProviderCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, u32, ValueQuery>;
+
+	/// Number of assets provided by each domain. Domains providing no asset have no entry.
+	#[pallet::storage]
+	#[pallet::getter(fn asset_count)]
+	pub(super) type AssetCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, u32, ValueQuery>;
 
+	/// Revalidation state of each domain providing at least one asset.
 	#[pallet::storage]
-	#[pallet::getter(fn last_processed_domain)]
-	pub(super) type LastProcessedDomain<T: Config> = StorageValue<_, Vec<u8>, ValueQuery>;
+	#[pallet::getter(fn provider_checks)]
+	pub(super) type ProviderChecks<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, ProviderCheck<BlockNumberFor<T>>, OptionQuery>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn next_request_id)]
+	pub(super) type NextRequestId<T: Config> = StorageValue<_, RequestId, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn requests)]
+	pub(super) type Requests<T: Config> =
+		StorageMap<_, Blake2_128Concat, RequestId, AssetRequest<T::AccountId>, OptionQuery>;
+
+	/// Identifier of each verified asset, by canonical hash.
+	#[pallet::storage]
+	#[pallet::getter(fn asset_ids)]
+	pub(super) type AssetIds<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, AssetId, OptionQuery>;
+
+	/// Description of each asset on the target chain of each of its providers, keyed by asset and
+	/// domain.
+	#[pallet::storage]
+	#[pallet::getter(fn asset_metadata)]
+	pub(super) type AssetMetadata<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		Blake2_128Concat,
+		Vec<u8>,
+		AssetInfo<T::AccountId, BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// Index of the `(asset, domain)` pairs described in [`AssetMetadata`] by asset name.
+	#[pallet::storage]
+	pub(super) type AssetsByName<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		Blake2_128Concat,
+		(Vec<u8>, Vec<u8>),
+		(),
+		OptionQuery,
+	>;
+
+	/// Index of the requests made by each account.
+	#[pallet::storage]
+	pub(super) type RequesterRequests<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		RequestId,
+		(),
+		OptionQuery,
+	>;
+
+	/// Deposit reserved for each open request or pending withdrawal, along with the account it
+	/// was reserved from.
+	#[pallet::storage]
+	#[pallet::getter(fn request_deposits)]
+	pub(super) type RequestDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, RequestId, (T::AccountId, BalanceOf<T>), OptionQuery>;
+
+	/// Number of open requests and pending withdrawals of each account.
 	#[pallet::storage]
 	#[pallet::getter(fn pending_requests)]
 	pub(super) type PendingRequests<T: Config> =
-		StorageMap<_, Blake2_128Concat, Vec<u8>, PendingRequest, OptionQuery>;
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// Attestations of the offchain worker authorities about subjects whose outcome did not
+	/// reach the threshold yet, with the block they were made at.
+	#[pallet::storage]
+	#[pallet::getter(fn attestations)]
+	pub(super) type Attestations<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		AttestationSubject,
+		Blake2_128Concat,
+		T::AccountId,
+		(AttestationOutcome, BlockNumberFor<T>),
+		OptionQuery,
+	>;
+
+	/// Number of times each authority attested against the settled outcome.
+	#[pallet::storage]
+	#[pallet::getter(fn dissents)]
+	pub(super) type Dissents<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// Pending withdrawals, sharing their ids with the requests.
+	#[pallet::storage]
+	#[pallet::getter(fn withdrawals)]
+	pub(super) type Withdrawals<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		RequestId,
+		Withdrawal<T::AccountId, BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// Index of the open requests and pending withdrawals by the block they expire at.
+	#[pallet::storage]
+	pub(super) type RequestExpiries<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		Twox64Concat,
+		RequestId,
+		(),
+		OptionQuery,
+	>;
+
+	/// First block of [`RequestExpiries`] whose requests were not all expired yet.
+	#[pallet::storage]
+	pub(super) type NextExpiryBlock<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+	/// Domain map layout of each TLD chain, derived from its runtime metadata.
+	///
+	/// TLD chains without an entry are assumed to run the current `pallet_tld` under the
+	/// configured prefixes.
+	#[pallet::storage]
+	#[pallet::getter(fn tld_layouts)]
+	pub(super) type TldLayouts<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, DomainMapLayout, OptionQuery>;
+
+	/// Domains whose asset manifest is ingested by the offchain worker.
+	#[pallet::storage]
+	#[pallet::getter(fn manifest_states)]
+	pub(super) type ManifestStates<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, ManifestState<BlockNumberFor<T>>, OptionQuery>;
+
+	/// Deposit reserved for each tracked asset manifest, along with the account it was reserved
+	/// from.
+	#[pallet::storage]
+	#[pallet::getter(fn manifest_deposits)]
+	pub(super) type ManifestDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, (T::AccountId, BalanceOf<T>), OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn parameters)]
+	pub(super) type Parameters<T: Config> = StorageValue<_, ParameterOverrides, ValueQuery>;
+
+	/// Accounts of the offchain worker authorities whose signed payloads are accepted.
+	#[pallet::storage]
+	#[pallet::getter(fn ocw_authorities)]
+	pub(super) type OcwAuthorities<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		pub authorities: Vec<T::AccountId>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			assert!(
+				self.authorities.len() <= T::MaxAuthorities::get() as usize,
+				"More offchain worker authorities than `MaxAuthorities`"
+			);
+			OcwAuthorities::<T>::put(&self.authorities);
+		}
+	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub (super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		DomainValidationRequested(T::AccountId, PendingRequest),
+		DomainValidationRequested(T::AccountId, RequestId, AssetRequest<T::AccountId>),
 		AssetRegisteredForDomain(Vec<u8>, Vec<u8>, U256),
 		AssetProviderRevoked(Vec<u8>),
-		TesterEvent(Vec<(T::AccountId, PendingRequest)>),
-		ExpiredRequestsRemoved,
+		TesterEvent(Vec<(T::AccountId, AssetRequest<T::AccountId>)>),
+		OcwAuthoritiesSet(Vec<T::AccountId>),
+		RequestStatusChanged(RequestId, RequestStatus),
+		ParametersSet(ParameterOverrides),
+		TldLayoutSet(Vec<u8>, DomainMapLayout),
+		/// The description of an asset (first) on the target chain of a domain (second) was
+		/// updated
+		AssetMetadataUpdated(Vec<u8>, Vec<u8>),
+		/// The domain was proven to still be registered
+		ProviderRevalidated(Vec<u8>),
+		/// The TLD chain of the domain could not be reached, for the given number of times in a
+		/// row
+		RevalidationFailed(Vec<u8>, u32),
+		/// The asset manifest of the domain will be ingested
+		ManifestTracked(Vec<u8>),
+		/// The asset manifest of the domain with the given hash was ingested, registering the
+		/// first number of assets and deregistering the second
+		ManifestIngested(Vec<u8>, H256, u32, u32),
+		/// The asset manifest of the domain is no longer tracked, because the domain stopped
+		/// publishing it or its depositor untracked it, deregistering the given number of assets
+		ManifestUntracked(Vec<u8>, u32),
+		/// The deposit for tracking the asset manifest of the domain was returned to the account
+		ManifestDepositReturned(Vec<u8>, T::AccountId, BalanceOf<T>),
+		/// The account asked to withdraw the domain from the providers of the asset, or of all its
+		/// assets when `None`
+		WithdrawalRequested(T::AccountId, RequestId, Vec<u8>, Option<Vec<u8>>),
+		/// The domain (second) stopped providing the asset (first) at the request of its owner
+		AssetWithdrawn(Vec<u8>, Vec<u8>),
+		/// The requester of the withdrawal neither owns nor manages the domain
+		WithdrawalRejected(RequestId),
+		/// The withdrawal was not checked within its lifetime
+		WithdrawalExpired(RequestId),
+		/// The deposit of the request was returned to the account, minus the slashed amount
+		DepositSettled(RequestId, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// The authority attested an outcome that did not reach the threshold yet
+		Attested(T::AccountId, AttestationSubject, AttestationOutcome),
+		/// The outcome reached the threshold of matching attestations
+		AttestationSettled(AttestationSubject, AttestationOutcome),
+		/// The authority attested against the settled outcome, for the given number of times
+		AuthorityDissented(T::AccountId, AttestationSubject, u32),
+		/// The proof that the domain is revoked was rejected, the other domains of the batch are
+		/// still processed
+		RevocationProofRejected(Vec<u8>, DispatchError),
 	}
 
 	#[pallet::error]
@@ -99,6 +732,72 @@ pub mod pallet {
 		DomainInvalid,
 		RequestFailed,
 		RequestDoesNotExist,
+		/// The storage proof does not cover the domain or does not match its header
+		InvalidProof,
+		/// The proven domain information could not be decoded
+		InvalidDomainInfo,
+		/// The proof was taken at a header the light client does not know as finalized
+		UnknownHeader,
+		/// The proof was taken at a header older than `T::MaxProofAge` blocks
+		StaleProof,
+		/// The target chain of the domain has no asset with the requested id
+		AssetNotFound,
+		/// The domain committed no header of its target chain on its TLD chain
+		NoTargetHeader,
+		/// The proof from the target chain was not taken at the header committed by the domain
+		UnanchoredProof,
+		/// The proof shows the asset exists, so the request cannot be rejected
+		AssetExists,
+		/// The domain is registered, so a proof that the asset is missing is required
+		MissingAssetProof,
+		/// The request was already verified, rejected or expired
+		RequestNotOpen,
+		/// Batch sizes, cadences and storage prefixes cannot be zero or empty
+		InvalidParameter,
+		/// A domain map layout must name its pallet and storage item
+		InvalidLayout,
+		/// The proven asset description could not be decoded
+		InvalidAssetInfo,
+		/// The asset identifier is malformed
+		InvalidAssetId,
+		/// Assets of this standard cannot be verified yet
+		UnsupportedStandard,
+		/// The asset lives on another chain than the target chain of the domain
+		ChainMismatch,
+		/// The authorization was not signed by the given authorizer
+		InvalidAuthorization,
+		/// The authorization can no longer be used
+		AuthorizationExpired,
+		/// The authorizer neither owns nor manages the domain on its TLD chain
+		NotAuthorized,
+		/// The asset manifest of the domain is already ingested
+		ManifestAlreadyTracked,
+		/// The asset manifest of the domain is not ingested
+		ManifestNotTracked,
+		/// The proven asset manifest record could not be decoded
+		InvalidManifest,
+		/// The listed assets do not hash to the content hash of the proven manifest
+		ManifestMismatch,
+		/// The manifest lists more than `MAX_MANIFEST_ENTRIES` assets
+		ManifestTooLarge,
+		/// The domain provides more assets than the submission accounts for
+		InvalidAssetCount,
+		/// The domain does not provide the asset, or any asset
+		NotProvider,
+		/// The withdrawal does not exist or expired
+		WithdrawalDoesNotExist,
+		/// The account has too many requests and withdrawals pending
+		TooManyPendingRequests,
+		/// The account cannot afford the deposit
+		InsufficientDeposit,
+		/// Only the account that reserved the deposit can stop tracking the manifest
+		NotManifestDepositor,
+		/// More offchain worker authorities than `T::MaxAuthorities`
+		TooManyAuthorities,
+		/// The refresh describes more than `MAX_REFRESHED_ASSETS` assets
+		TooManyAssets,
+		/// The submission covers more requests than the domain batch size
+		BatchTooLarge,
 	}
 
 	fn extract_tld(domain: &[u8]) -> Option<&[u8]> {
@@ -115,12 +814,12 @@ pub mod pallet {
 		boot_nodes: Vec<String>,
 	}
 
-	fn fetch_json_from_url(url: &str) -> Result<Chainspec, &'static str> {
+	fn fetch_json_from_url(url: &str, timeout_ms: u64) -> Result<Chainspec, &'static str> {
 		let request = http::Request::get(url);
 
 		let response = match request.send() {
 			Ok(resp) => {
-				let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(5000));
+				let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(timeout_ms));
 				match resp.try_wait(deadline) {
 					Ok(response) => match response {
 						Ok(r) => r,
@@ -163,392 +862,2203 @@ pub mod pallet {
 		result
 	}
 
-	fn extract_rpc_endpoint(multiaddr: String) -> String {
-		let parts: Vec<&str> = multiaddr.split('/').collect();
-		let ip = if parts.len() > 2 { parts[2] } else { "" };
-		let port = if parts.len() > 4 { parts[4] } else { "" };
-		format!("http://{}:{}", ip, port)
+	/// Storage key of `asset_hash`, the SCALE encoded asset id, in the `storage_prefix` map of the
+	/// `pallet_assets` instance named `pallet_prefix` of a target chain.
+	fn asset_storage_key(
+		pallet_prefix: &[u8],
+		storage_prefix: &[u8],
+		asset_hash: &[u8],
+	) -> Vec<u8> {
+		let mut storage_key = vec![];
+		storage_key.extend(sp_core::hashing::twox_128(pallet_prefix));
+		storage_key.extend(sp_core::hashing::twox_128(storage_prefix));
+		storage_key.extend(blake2_128_concat(asset_hash.to_vec()));
+		storage_key
+	}
+
+	/// Storage key of the genesis hash in the `BlockHash` map of `frame_system` of a target chain.
+	fn genesis_hash_storage_key() -> Vec<u8> {
+		let mut storage_key = vec![];
+		storage_key.extend(sp_core::hashing::twox_128(SYSTEM_MODULE_PREFIX));
+		storage_key.extend(sp_core::hashing::twox_128(BLOCK_HASH_STORAGE_PREFIX));
+		storage_key.extend(sp_core::hashing::twox_64(&0u32.encode()));
+		storage_key.extend(0u32.encode());
+		storage_key
+	}
+
+	/// Records of `pallet_tld` kept about a domain besides its entry in the domain map.
+	#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+	pub enum TldRecord {
+		/// Whether the SCALE encoded account manages the domain
+		Manager(Vec<u8>),
+		/// The asset manifest published by the domain
+		Manifest,
+		/// The header of the target chain committed by the domain
+		TargetHeader,
 	}
 
-	#[derive(Serialize, Deserialize, Debug)]
-	struct RPCResponse {
-		jsonrpc: String,
-		result: String,
-		id: u32,
+	impl TldRecord {
+		/// Storage key of the record about `domain` in the `pallet_tld` described by `layout`.
+		fn storage_key(&self, layout: &DomainMapLayout, domain: &[u8]) -> Vec<u8> {
+			let storage_prefix = match self {
+				TldRecord::Manager(_) => TLD_MANAGERS_STORAGE_PREFIX,
+				TldRecord::Manifest => TLD_MANIFESTS_STORAGE_PREFIX,
+				TldRecord::TargetHeader => TLD_TARGET_HEADERS_STORAGE_PREFIX,
+			};
+			let mut storage_key = vec![];
+			storage_key.extend(sp_core::hashing::twox_128(&layout.pallet_prefix));
+			storage_key.extend(sp_core::hashing::twox_128(storage_prefix));
+			storage_key.extend(blake2_128_concat(domain.encode()));
+			if let TldRecord::Manager(account) = self {
+				storage_key.extend(blake2_128_concat(account.clone()));
+			}
+			storage_key
+		}
 	}
 
 	#[pallet::validate_unsigned]
 	impl<T: Config> ValidateUnsigned for Pallet<T> {
 		type Call = Call<T>;
 
-		fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
-			// match source {
-			// 	TransactionSource::Local | TransactionSource::External => {
-			// Allow unsigned transactions only from the Offchain worker.
-			if let Call::submit_verified_domain { account_id, pending_request } = call {
-				let tag =
-					(pending_request.domain.clone(), pending_request.asset_hash.clone()).encode();
-				return ValidTransaction::with_tag_prefix("offchain_worker")
-					.priority(TransactionPriority::MAX) // Highest priority
-					.longevity(1)
-					.propagate(true)
-					.build();
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			// Only payloads signed by an offchain worker authority are accepted. A verification and
+			// a rejection of the same request by the same authority provide the same tag, so only
+			// one of them is kept, while every authority can attest.
+			match call {
+				Call::submit_verified_domain { payload, signature } => {
+					let tag = (b"request", payload.data.request_id, &payload.public).encode();
+					Self::validate_payload(payload, signature, "offchain_worker", vec![tag])
+				},
+				Call::reject_pending_request { payload, signature } => {
+					let tag = (b"request", payload.data.request_id, &payload.public).encode();
+					Self::validate_payload(payload, signature, "offchain_worker", vec![tag])
+				},
+				Call::begin_verification { payload, signature } => {
+					let tag = (b"verifying", &payload.data.request_ids).encode();
+					Self::validate_payload(payload, signature, "offchain_worker", vec![tag])
+				},
+				Call::set_tld_layout { payload, signature } => {
+					let tag = (b"layout", &payload.data.tld, &payload.public).encode();
+					Self::validate_payload(payload, signature, "offchain_worker", vec![tag])
+				},
+				Call::refresh_asset_metadata { payload, signature } => {
+					let tag = (b"metadata", &payload.data.domain, &payload.public).encode();
+					Self::validate_payload(payload, signature, "offchain_worker", vec![tag])
+				},
+				Call::report_revalidation_failures { payload, signature } => {
+					let tag = (b"failures", &payload.data.domains).encode();
+					Self::validate_payload(payload, signature, "offchain_worker", vec![tag])
+				},
+				Call::complete_withdrawal { payload, signature } => {
+					let tag = (b"withdrawal", payload.data.request_id, &payload.public).encode();
+					Self::validate_payload(payload, signature, "offchain_worker", vec![tag])
+				},
+				Call::ingest_manifest { payload, signature } => {
+					let tag = (b"manifest", &payload.data.domain, &payload.public).encode();
+					Self::validate_payload(payload, signature, "offchain_worker", vec![tag])
+				},
+				Call::cleanup_revoked_domains { payload, signature } => {
+					let tags = payload
+						.data
+						.domains
+						.iter()
+						.map(|(domain, _)| (b"revoked", domain, &payload.public).encode())
+						.collect();
+					Self::validate_payload(payload, signature, "offchain_worker", tags)
+				},
+				_ => InvalidTransaction::Call.into(),
 			}
+		}
+	}
 
-			if let Call::remove_expired_pending_requests { .. } = call {
-				return ValidTransaction::with_tag_prefix("offchain_worker_expired_requests")
-					.priority(TransactionPriority::MAX) // Highest priority
-					.longevity(1)
-					.propagate(true)
-					.build();
+	impl<T: Config> Pallet<T> {
+		/// Checks that `payload` is recent and signed by an offchain worker authority.
+		fn validate_payload<Data: Encode>(
+			payload: &OcwPayloadOf<T, Data>,
+			signature: &T::Signature,
+			tag_prefix: &'static str,
+			tags: Vec<Vec<u8>>,
+		) -> TransactionValidity {
+			let current_block = <frame_system::Pallet<T>>::block_number();
+			if payload.block_number > current_block {
+				return InvalidTransaction::Future.into();
 			}
-
-			if let Call::cleanup_revoked_domains { .. } = call {
-				return ValidTransaction::with_tag_prefix("offchain_worker_cleanup_revoked")
-					.priority(TransactionPriority::MAX) // Highest priority
-					.longevity(1)
-					.propagate(true)
-					.build();
+			let expires_at = payload.block_number.saturating_add(PAYLOAD_LONGEVITY.into());
+			if expires_at <= current_block {
+				return InvalidTransaction::Stale.into();
 			}
 
-			// },
-			// // Reject if not from an offchain worker
-			// _ => return InvalidTransaction::Call.into(),
-			// }
+			let signer = payload.public.clone().into_account();
+			if !Self::ocw_authorities().contains(&signer) {
+				return InvalidTransaction::BadSigner.into();
+			}
+			if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
+				return InvalidTransaction::BadProof.into();
+			}
 
-			InvalidTransaction::Call.into()
+			tags.into_iter()
+				.fold(ValidTransaction::with_tag_prefix(tag_prefix), |builder, tag| {
+					builder.and_provides(tag)
+				})
+				.priority(TransactionPriority::MAX) // Highest priority
+				.longevity((expires_at - current_block).saturated_into::<u64>())
+				.propagate(true)
+				.build()
 		}
-	}
 
-	impl<T: Config> Pallet<T> {
-		fn fetch_pending_requests(
-			batch_size: usize,
-			current_time: U256,
-		) -> Vec<(Vec<u8>, PendingRequest)> {
-			PendingRequests::<T>::iter()
-				.filter_map(|(account, data)| {
-					if current_time <= data.timestamp {
-						Some((
-							account,
-							PendingRequest {
-								requester: data.requester,
-								domain: data.domain,
-								asset_hash: data.asset_hash,
-								timestamp: data.timestamp,
-							},
-						))
-					} else {
-						None
-					}
-				})
-				.take(batch_size)
-				.collect()
+		/// Signs `data` with a local offchain worker authority key and submits it as an unsigned
+		/// transaction built by `call`.
+		fn submit_signed_payload<Data: Encode + Clone>(
+			data: Data,
+			call: impl Fn(OcwPayloadOf<T, Data>, T::Signature) -> Call<T>,
+		) {
+			let block_number = <frame_system::Pallet<T>>::block_number();
+			let result = Signer::<T, T::AuthorityId>::any_account().send_unsigned_transaction(
+				|account| OcwPayload {
+					data: data.clone(),
+					block_number,
+					public: account.public.clone(),
+				},
+				call,
+			);
+
+			match result {
+				Some((_, Ok(()))) => {},
+				Some((account, Err(()))) =>
+					log::error!("[{:?}]: submit transaction failure.", account.id),
+				None => log::error!("No offchain worker authority key available to sign with"),
+			}
 		}
 
-		fn query_tld_network(domain: Vec<u8>) -> bool {
-			let tld = match extract_tld(&domain) {
-				Some(tld) => tld,
-				None => return false,
-			};
+		pub fn domain_batch_size() -> u32 {
+			Self::parameters().domain_batch_size.unwrap_or_else(T::DomainBatchSize::get)
+		}
 
-			let tld_info =
-				match pallet_rootdns::Pallet::<T::PalletRootDNS>::get_chainspec_for_tld(tld) {
-					Some(tld_info) => tld_info,
-					None => return false,
-				};
+		pub fn request_lifetime() -> u32 {
+			Self::parameters().request_lifetime.unwrap_or_else(T::RequestLifetime::get)
+		}
 
-			let spec = match fetch_json_from_url(str::from_utf8(&tld_info.chain_spec).unwrap()) {
-				Ok(chainspec) => chainspec,
-				Err(_) => return false,
-			};
+		pub fn ocw_cadence() -> u32 {
+			Self::parameters().ocw_cadence.unwrap_or_else(T::OcwCadence::get)
+		}
 
-			if spec.boot_nodes.is_empty() {
-				log::error!("No boot nodes found in chainspec");
-				return false;
-			}
+		pub fn http_timeout_ms() -> u64 {
+			Self::parameters().http_timeout_ms.unwrap_or_else(T::HttpTimeoutMs::get)
+		}
 
-			let multiaddr = spec.boot_nodes[0].clone(); // TODO: just take the first bootnode for now
-			let rpc_endpoint = extract_rpc_endpoint(multiaddr);
-			// Storage key calculation
-			let mut storage_key = vec![];
-			storage_key.extend(sp_core::hashing::twox_128(TLD_MODULE_PREFIX));
-			storage_key.extend(sp_core::hashing::twox_128(TLD_STORAGE_PREFIX));
-			storage_key.extend(blake2_128_concat(domain.clone().encode()));
+		pub fn revalidation_window() -> u32 {
+			Self::parameters()
+				.revalidation_window
+				.unwrap_or_else(T::RevalidationWindow::get)
+		}
 
-			let key_hex = hex::encode(storage_key);
+		/// Number of matching attestations that settle an outcome.
+		pub fn attestation_threshold() -> u32 {
+			let threshold = Self::parameters()
+				.attestation_threshold
+				.unwrap_or_else(T::AttestationThreshold::get);
+			threshold.min(Self::ocw_authorities().len() as u32).max(1)
+		}
 
-			const REQUEST_BODY: &str = r#"
-            {
-                "id":1,
-                "jsonrpc":"2.0",
-                "method": "state_getStorage",
-				"params": ["{}"]
-            }"#;
+		pub fn max_pending_requests() -> u32 {
+			Self::parameters()
+				.max_pending_requests
+				.unwrap_or_else(T::MaxPendingRequests::get)
+		}
 
-			let request_body = REQUEST_BODY.replace("{}", &key_hex);
+		/// Layout assumed for TLD chains whose metadata was not inspected yet.
+		fn default_tld_layout() -> DomainMapLayout {
+			let parameters = Self::parameters();
+			DomainMapLayout::current(
+				parameters.tld_module_prefix.unwrap_or_else(T::TldModulePrefix::get),
+				parameters.tld_storage_prefix.unwrap_or_else(T::TldStoragePrefix::get),
+			)
+		}
 
-			let req = http::Request::post(&rpc_endpoint, vec![request_body])
-				.add_header("content-type", "application/json")
-				.send()
-				.unwrap();
+		/// Name of the `pallet_assets` instance of the target chains.
+		pub fn assets_module_prefix() -> Vec<u8> {
+			Self::parameters()
+				.assets_module_prefix
+				.unwrap_or_else(T::AssetsModulePrefix::get)
+		}
 
-			let res = match req.wait() {
-				Ok(res) => res,
-				Err(err) => {
-					log::info!("Error sending request: {:?}", err);
-					return false;
-				},
-			};
+		pub fn tld_query_quorum() -> u32 {
+			Self::parameters().tld_query_quorum.unwrap_or_else(T::TldQueryQuorum::get)
+		}
 
-			if res.code == 200 {
-				let body = res.body().collect::<Vec<_>>();
-				let body_str = String::from_utf8(body).unwrap();
+		/// Domain map layout of the TLD chain `tld`.
+		pub fn tld_layout(tld: &[u8]) -> DomainMapLayout {
+			TldLayouts::<T>::get(tld).unwrap_or_else(Self::default_tld_layout)
+		}
 
-				let decoded_body = serde_json::from_str::<RPCResponse>(&body_str).unwrap();
-				let result_bytes =
-					hex::decode(decoded_body.result.trim_start_matches("0x")).unwrap();
+		/// Whether the offchain worker runs its periodic tasks at `current_time`.
+		fn is_cadence_block(current_time: U256) -> bool {
+			current_time % Self::ocw_cadence().max(1) == 0.into()
+		}
 
-				let domain_info =
-					pallet_tld::DomainInfo::<T::AccountId>::decode(&mut &result_bytes[..])
-						.expect("Failed to decode domain information");
-				return !domain_info.available;
-			} else {
-				log::info!("Request failed with code: {}", res.code);
-			}
-			false
+		/// Ids of the requests made by `who`.
+		pub fn requests_of(who: &T::AccountId) -> Vec<RequestId> {
+			RequesterRequests::<T>::iter_key_prefix(who).collect()
 		}
 
-		fn submit_domain_verification(account_id: Vec<u8>, pending_request: PendingRequest) {
-			let call = Call::submit_verified_domain { account_id, pending_request };
-			SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into())
-				.expect("Failed to submit unsigned transaction");
+		/// Block at which `request` expires.
+		fn expiry_block(request: &AssetRequest<T::AccountId>) -> BlockNumberFor<T> {
+			request.expires_at.low_u32().into()
 		}
 
-		fn cleanup_expired_requests(current_time: U256) {
-			if current_time % 10 == 0.into() {
-				log::info!("Cleaning up expired requests");
-				let call = Call::remove_expired_pending_requests { current_time };
-				SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into())
-					.expect("Failed to submit unsigned transaction");
+		fn set_request_status(
+			request_id: RequestId,
+			mut request: AssetRequest<T::AccountId>,
+			status: RequestStatus,
+		) {
+			if !status.is_open() {
+				RequestExpiries::<T>::remove(Self::expiry_block(&request), request_id);
+				Self::settle_deposit(request_id, status == RequestStatus::Verified);
+				Self::clear_attestations(&AttestationSubject::Request(request_id));
 			}
+			request.status = status;
+			Requests::<T>::insert(request_id, request);
+			Self::deposit_event(Event::RequestStatusChanged(request_id, status));
+		}
+
+		/// Fetches the open request `request_id`.
+		fn open_request(request_id: RequestId) -> Result<AssetRequest<T::AccountId>, Error<T>> {
+			let request = Requests::<T>::get(request_id).ok_or(Error::<T>::RequestDoesNotExist)?;
+			let current_block = <frame_system::Pallet<T>>::block_number();
+			// Requests past their expiry are closed even before `on_idle` gets to them
+			ensure!(
+				request.status.is_open() && current_block < Self::expiry_block(&request),
+				Error::<T>::RequestNotOpen
+			);
+			Ok(request)
 		}
 
-		fn get_domain_batch(batch_size: usize) -> Result<Vec<Vec<u8>>, &'static str> {
-			let mut keys = Vec::new();
-			let mut key_count = 0;
-			let last_key = LastProcessedDomain::<T>::get();
+		/// Marks the open requests whose expiry block was reached as expired and drops the pending
+		/// withdrawals, oldest first, within `limit`, and returns the weight used.
+		///
+		/// Processing resumes where it stopped in the next call once the weight runs out.
+		fn expire_requests(now: BlockNumberFor<T>, limit: Weight) -> Weight {
+			let db_weight = T::DbWeight::get();
+			// Reading and writing the cursor
+			let mut used = db_weight.reads_writes(1, 1);
+			// Reading the first requests expiring at a block
+			let per_block = db_weight.reads(1);
+			// Reading a request, then closing it
+			let per_request = db_weight.reads(1).saturating_add(Self::closing_weight());
+			if used.saturating_add(per_block).any_gt(limit) {
+				return Weight::zero();
+			}
 
-			let iter = match last_key.is_empty() {
-				true => ProviderAssets::<T>::iter(),
-				false => ProviderAssets::<T>::iter_from(last_key),
-			};
+			let mut block = NextExpiryBlock::<T>::get();
+			while block <= now && !used.saturating_add(per_block).any_gt(limit) {
+				used.saturating_accrue(per_block);
+
+				let remaining = limit.saturating_sub(used);
+				let chunk = (remaining.ref_time() / per_request.ref_time().max(1))
+					.min(remaining.proof_size() / per_request.proof_size().max(1));
+				// Collected first, as the index is modified while expiring them
+				let request_ids: Vec<RequestId> = RequestExpiries::<T>::iter_key_prefix(block)
+					.take(chunk.saturating_add(1).saturated_into())
+					.collect();
+				let drained = request_ids.len() as u64 <= chunk;
+
+				for request_id in request_ids.into_iter().take(chunk.saturated_into()) {
+					used.saturating_accrue(per_request);
+					RequestExpiries::<T>::remove(block, request_id);
+					match Requests::<T>::get(request_id) {
+						Some(request) if request.status.is_open() =>
+							Self::set_request_status(request_id, request, RequestStatus::Expired),
+						Some(_) => {},
+						None =>
+							if Withdrawals::<T>::take(request_id).is_some() {
+								Self::settle_deposit(request_id, false);
+								Self::clear_attestations(&AttestationSubject::Request(request_id));
+								Self::deposit_event(Event::WithdrawalExpired(request_id));
+							},
+					}
+				}
 
-			for (key, _value) in iter {
-				if key_count >= batch_size {
+				if !drained {
 					break;
 				}
-				keys.push(key);
-				key_count += 1;
+				block += One::one();
 			}
 
-			if let Some(last_key) = keys.last() {
-				LastProcessedDomain::<T>::put(last_key);
-			}
+			NextExpiryBlock::<T>::put(block);
+			used
+		}
 
-			Ok(keys)
+		/// Allocates the id of a new request or withdrawal.
+		fn take_request_id() -> Result<RequestId, Error<T>> {
+			let request_id = NextRequestId::<T>::get();
+			NextRequestId::<T>::put(request_id.checked_add(1).ok_or(Error::<T>::RequestFailed)?);
+			Ok(request_id)
 		}
 
-		fn remove_revoked_domains(domain_batch: Vec<Vec<u8>>) {
-			let mut revoked_domains = Vec::new();
+		/// Reserves the deposit of the new request `request_id` from `who`, within the cap on
+		/// pending requests.
+		fn reserve_deposit(who: &T::AccountId, request_id: RequestId) -> DispatchResult {
+			let pending = PendingRequests::<T>::get(who);
+			ensure!(pending < Self::max_pending_requests(), Error::<T>::TooManyPendingRequests);
 
-			for domain in domain_batch {
-				let is_valid = Self::query_tld_network(domain.clone());
-				if !is_valid {
-					revoked_domains.push(domain.clone());
-				}
-			}
+			let deposit = T::RequestDeposit::get();
+			T::Currency::reserve(who, deposit).map_err(|_| Error::<T>::InsufficientDeposit)?;
+			RequestDeposits::<T>::insert(request_id, (who.clone(), deposit));
+			PendingRequests::<T>::insert(who, pending.saturating_add(1));
+			Ok(())
+		}
 
-			if revoked_domains.is_empty() {
+		/// Returns the deposit of a closed request to its account, slashing `T::RequestSlash` of
+		/// it into the treasury unless the request succeeded.
+		fn settle_deposit(request_id: RequestId, succeeded: bool) {
+			let Some((who, deposit)) = RequestDeposits::<T>::take(request_id) else {
 				return;
-			}
-
-			let call = Call::cleanup_revoked_domains { domains: revoked_domains };
-			SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into())
-				.expect("Failed to submit unsigned transaction");
+			};
+			PendingRequests::<T>::mutate_exists(&who, |pending| {
+				*pending =
+					pending.map(|pending| pending.saturating_sub(1)).filter(|pending| *pending > 0)
+			});
+
+			let slash =
+				if succeeded { Zero::zero() } else { T::RequestSlash::get().mul_floor(deposit) };
+			// Unlike a transfer, slashing does not depend on the treasury account existing. What
+			// the treasury cannot receive because it would stay below the existential deposit is
+			// burned.
+			let (slashed, _) = T::Currency::slash_reserved(&who, slash);
+			let slash = slashed.peek();
+			T::Currency::resolve_creating(&T::TreasuryAccount::get(), slashed);
+			T::Currency::unreserve(&who, deposit.saturating_sub(slash));
+			Self::deposit_event(Event::DepositSettled(request_id, who, deposit, slash));
 		}
-	}
 
-	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-		fn offchain_worker(block_number: BlockNumberFor<T>) {
-			let current_time = block_number.into();
-			if current_time % 10 == 0.into() {
-				Self::remove_revoked_domains(Self::get_domain_batch(DOMAIN_BATCH_SIZE).unwrap())
-			}
-
-			Self::cleanup_expired_requests(current_time);
+		/// Weight of closing a request: writing it, removing its index entry, settling its deposit
+		/// from the deposit and pending count entries and the requester and treasury balances, and
+		/// clearing its attestations.
+		pub fn closing_weight() -> Weight {
+			T::DbWeight::get().reads_writes(4, 6 + T::MaxAuthorities::get() as u64)
+		}
 
-			let batch_size = 10;
-			let pending_requests = Self::fetch_pending_requests(batch_size, current_time);
+		/// Weight of an attestation, which reads, prunes, clears and reports the attestations of
+		/// up to `T::MaxAuthorities` authorities.
+		pub fn attestation_weight() -> Weight {
+			let authorities = T::MaxAuthorities::get() as u64;
+			T::DbWeight::get().reads_writes(2 + 2 * authorities, 1 + 3 * authorities)
+		}
 
-			for request in pending_requests {
-				let domain = request.1.domain.clone();
+		/// Clears the attestations about `subject`.
+		///
+		/// At most `T::MaxAuthorities` entries are removed. Any left by former authorities are
+		/// never counted, as only current authorities are looked up.
+		fn clear_attestations(subject: &AttestationSubject) {
+			let _ = Attestations::<T>::clear_prefix(subject, T::MaxAuthorities::get(), None);
+		}
 
-				let domain_valid = Self::query_tld_network(domain.clone());
+		/// Records the attestation of `authority` about `subject` and returns whether `outcome`
+		/// reached the threshold.
+		///
+		/// Once it does, the attestations about `subject` are cleared and the authorities that
+		/// attested another outcome are reported. Attestations of former authorities are not
+		/// counted, and attestations older than `T::AttestationWindow` are discarded, as the
+		/// subject may have changed since, e.g. a revoked domain registered again.
+		fn attest(
+			subject: AttestationSubject,
+			authority: T::AccountId,
+			outcome: AttestationOutcome,
+		) -> bool {
+			let now = <frame_system::Pallet<T>>::block_number();
+			Attestations::<T>::insert(&subject, &authority, (outcome, now));
+
+			let window: BlockNumberFor<T> = T::AttestationWindow::get().into();
+			let mut attestations: Vec<(T::AccountId, AttestationOutcome)> = Vec::new();
+			for attester in Self::ocw_authorities() {
+				match Attestations::<T>::get(&subject, &attester) {
+					Some((_, attested_at)) if attested_at.saturating_add(window) <= now =>
+						Attestations::<T>::remove(&subject, &attester),
+					Some((attested, _)) => attestations.push((attester, attested)),
+					None => {},
+				}
+			}
+			let matching = attestations.iter().filter(|(_, attested)| *attested == outcome).count();
+			if (matching as u32) < Self::attestation_threshold() {
+				Self::deposit_event(Event::Attested(authority, subject, outcome));
+				return false;
+			}
 
-				if domain_valid {
-					let _ = Self::submit_domain_verification(request.0, request.1);
+			Self::clear_attestations(&subject);
+			Self::deposit_event(Event::AttestationSettled(subject.clone(), outcome));
+			for (attester, attested) in attestations {
+				if attested != outcome {
+					let dissents = Dissents::<T>::mutate(&attester, |dissents| {
+						*dissents = dissents.saturating_add(1);
+						*dissents
+					});
+					Self::deposit_event(Event::AuthorityDissented(
+						attester,
+						subject.clone(),
+						dissents,
+					));
 				}
 			}
+			true
 		}
-	}
 
-	#[pallet::call]
-	impl<T: Config> Pallet<T> {
-		#[pallet::call_index(0)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
-		pub fn submit_verified_domain(
-			origin: OriginFor<T>,
-			account_id: Vec<u8>,
-			pending_request: PendingRequest,
+		/// Registers a withdrawal to be checked by the offchain worker.
+		fn request_withdrawal(
+			who: T::AccountId,
+			domain: Vec<u8>,
+			asset_hash: Option<Vec<u8>>,
 		) -> DispatchResult {
-			// Only allow off-chain workers to call this function
-			ensure_none(origin)?;
-
 			let current_block_number = <frame_system::Pallet<T>>::block_number();
+			let expires_at = current_block_number.saturating_add(Self::request_lifetime().into());
 
-			let mut key = vec![];
-			key.append(&mut pending_request.asset_hash.clone());
-			key.append(&mut pending_request.domain.clone());
+			let request_id = Self::take_request_id()?;
+			Self::reserve_deposit(&who, request_id)?;
+			let withdrawal = Withdrawal {
+				requester: who.clone(),
+				domain: domain.clone(),
+				asset_hash: asset_hash.clone(),
+				expires_at,
+			};
+			Withdrawals::<T>::insert(request_id, withdrawal);
+			RequestExpiries::<T>::insert(expires_at, request_id, ());
 
-			let request_exists = <PendingRequests<T>>::contains_key(key);
+			Self::deposit_event(Event::WithdrawalRequested(who, request_id, domain, asset_hash));
+			Ok(())
+		}
 
-			if !request_exists {
-				return Err(Error::<T>::RequestDoesNotExist.into());
+		/// Number of assets `withdrawal` deregisters the domain from.
+		fn withdrawn_assets(withdrawal: &Withdrawal<T::AccountId, BlockNumberFor<T>>) -> u32 {
+			match withdrawal.asset_hash {
+				Some(_) => 1,
+				None => AssetCount::<T>::get(&withdrawal.domain),
 			}
+		}
 
-			PendingRequests::<T>::remove(account_id);
+		fn fetch_pending_withdrawals(
+			batch_size: usize,
+		) -> Vec<(RequestId, Withdrawal<T::AccountId, BlockNumberFor<T>>)> {
+			let current_block_number = <frame_system::Pallet<T>>::block_number();
+			Withdrawals::<T>::iter()
+				.filter(|(_, withdrawal)| current_block_number < withdrawal.expires_at)
+				.take(batch_size)
+				.collect()
+		}
 
-			match AssetProviders::<T>::get(&pending_request.asset_hash) {
-				Some(provider_list) => {
-					let mut providers = provider_list.providers.clone();
-					providers.push(pending_request.domain.clone());
-					AssetProviders::<T>::insert(
-						pending_request.asset_hash.clone(),
-						ProviderList { providers },
-					);
-				},
-				None => {
-					AssetProviders::<T>::insert(
-						pending_request.asset_hash.clone(),
-						ProviderList { providers: vec![pending_request.domain.clone()] },
-					);
-				},
-			}
+		/// Open requests to verify in this run.
+		///
+		/// Only open requests and withdrawals are indexed by expiry, so requests closed earlier
+		/// are never visited.
+		fn fetch_open_requests(
+			batch_size: usize,
+			current_time: U256,
+		) -> Vec<(RequestId, AssetRequest<T::AccountId>)> {
+			RequestExpiries::<T>::iter_keys()
+				.filter_map(|(_, request_id)| {
+					Requests::<T>::get(request_id).map(|request| (request_id, request))
+				})
+				.filter(|(_, request)| {
+					request.status.is_open() && current_time < request.expires_at
+				})
+				.take(batch_size)
+				.collect()
+		}
 
-			match ProviderAssets::<T>::get(&pending_request.domain) {
-				Some(asset_list) => {
-					let mut assets = asset_list.assets.clone();
-					assets.push(pending_request.asset_hash.clone());
-					ProviderAssets::<T>::insert(
-						pending_request.domain.clone(),
-						AssetList { assets },
-					);
-				},
-				None => {
-					ProviderAssets::<T>::insert(
-						pending_request.domain.clone(),
-						AssetList { assets: vec![pending_request.asset_hash.clone()] },
-					);
-				},
-			}
+		/// Checks that a read proof from the TLD chain `tld` was taken at a recent header finalized
+		/// by its light client.
+		///
+		/// TLD chains the light client does not follow cannot be proven at all. Older headers are
+		/// refused too, as their state may predate the registration or revocation of a domain.
+		fn check_tld_header(tld: &[u8], proof: &ProvenState) -> Result<(), Error<T>> {
+			let header = pallet_lightclient::Pallet::<T::PalletLightClient>::finalized_header(
+				tld,
+				proof.header.hash(),
+			)
+			.ok_or(Error::<T>::UnknownHeader)?;
+			ensure!(header.state_root == proof.header.state_root, Error::<T>::InvalidProof);
+
+			let (best_number, _) =
+				pallet_lightclient::Pallet::<T::PalletLightClient>::best_finalized(tld)
+					.ok_or(Error::<T>::UnknownHeader)?;
+			ensure!(
+				header.number.saturating_add(T::MaxProofAge::get()) >= best_number,
+				Error::<T>::StaleProof
+			);
+			Ok(())
+		}
 
-			Self::deposit_event(Event::AssetRegisteredForDomain(
-				pending_request.asset_hash,
-				pending_request.domain,
-				current_block_number.into(),
-			));
+		/// Checks a read proof of `domain` from its TLD chain and returns whether the domain is
+		/// registered there.
+		fn check_domain_proof(domain: &[u8], proof: &ProvenState) -> Result<bool, Error<T>> {
+			Ok(Self::proven_domain_info(domain, proof)?.is_some())
+		}
 
-			Ok(())
+		/// Checks a read proof of `domain` from its TLD chain and returns its record if the domain
+		/// is registered there.
+		fn proven_domain_info(
+			domain: &[u8],
+			proof: &ProvenState,
+		) -> Result<Option<pallet_tld::DomainInfo<T::AccountId>>, Error<T>> {
+			let tld = extract_tld(domain).ok_or(Error::<T>::DomainInvalid)?;
+			Self::check_tld_header(tld, proof)?;
+
+			let layout = Self::tld_layout(tld);
+			let value = proof
+				.read_value(&layout.storage_key(domain))
+				.map_err(|_| Error::<T>::InvalidProof)?;
+
+			match value {
+				Some(bytes) => {
+					let domain_info =
+						metadata::decode_domain_info::<T::AccountId>(layout.version, &bytes)
+							.map_err(|_| Error::<T>::InvalidDomainInfo)?;
+					Ok((!domain_info.available).then_some(domain_info))
+				},
+				None => Ok(None),
+			}
 		}
 
-		#[pallet::call_index(1)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
-		pub fn register_asset_for_domain(
-			origin: OriginFor<T>,
-			domain: Vec<u8>,
-			asset_hash: Vec<u8>,
-		) -> DispatchResult {
-			let who = ensure_signed(origin)?;
-			let current_block_number = <frame_system::Pallet<T>>::block_number();
-			let lifetime = current_block_number.into() + REQUEST_LIFETIME;
+		/// Returns whether `account` is, according to a read proof from the TLD chain, the creator
+		/// of the registered `domain` or one of its managers.
+		fn is_authorized(
+			domain: &[u8],
+			account: &T::AccountId,
+			domain_info: &pallet_tld::DomainInfo<T::AccountId>,
+			proof: &ProvenState,
+		) -> Result<bool, Error<T>> {
+			if &domain_info.creator == account {
+				return Ok(true);
+			}
 
-			let request = PendingRequest {
-				requester: who.clone().encode(),
-				domain: domain.clone(),
-				asset_hash: asset_hash.clone(),
-				timestamp: lifetime,
-			};
+			let tld = extract_tld(domain).ok_or(Error::<T>::DomainInvalid)?;
+			let key =
+				TldRecord::Manager(account.encode()).storage_key(&Self::tld_layout(tld), domain);
+			let value = proof.read_value(&key).map_err(|_| Error::<T>::InvalidProof)?;
+			Ok(value.is_some())
+		}
 
-			let mut key = vec![];
-			key.append(&mut asset_hash.clone());
-			key.append(&mut domain.clone());
+		/// Reads the asset manifest published by `domain` from a read proof of its TLD chain.
+		///
+		/// The domain itself must be proven with [`Self::proven_domain_info`] first.
+		fn proven_manifest(
+			domain: &[u8],
+			proof: &ProvenState,
+		) -> Result<Option<pallet_tld::AssetManifest>, Error<T>> {
+			let tld = extract_tld(domain).ok_or(Error::<T>::DomainInvalid)?;
+			let key = TldRecord::Manifest.storage_key(&Self::tld_layout(tld), domain);
+			let value = proof.read_value(&key).map_err(|_| Error::<T>::InvalidProof)?;
+			value
+				.map(|bytes| pallet_tld::AssetManifest::decode(&mut &bytes[..]))
+				.transpose()
+				.map_err(|_| Error::<T>::InvalidManifest)
+		}
 
-			// Request ocw to validate domain
-			PendingRequests::<T>::insert(key, request.clone());
+		/// Reads the header of the target chain committed by `domain` from a read proof of its TLD
+		/// chain.
+		///
+		/// The domain itself must be proven with [`Self::proven_domain_info`] first.
+		fn proven_target_header(
+			domain: &[u8],
+			proof: &ProvenState,
+		) -> Result<Option<H256>, Error<T>> {
+			let tld = extract_tld(domain).ok_or(Error::<T>::DomainInvalid)?;
+			let key = TldRecord::TargetHeader.storage_key(&Self::tld_layout(tld), domain);
+			let value = proof.read_value(&key).map_err(|_| Error::<T>::InvalidProof)?;
+			value
+				.map(|bytes| H256::decode(&mut &bytes[..]))
+				.transpose()
+				.map_err(|_| Error::<T>::InvalidProof)
+		}
 
-			// Emit event
-			Self::deposit_event(Event::DomainValidationRequested(who, request));
+		/// Checks that a read proof from the target chain of `domain` was taken at the header the
+		/// domain committed on its TLD chain, as proven by `proof`.
+		///
+		/// Target chains are not followed by the light client, so this commitment is what ties
+		/// their proofs to a header the domain vouches for.
+		fn check_anchored(
+			domain: &[u8],
+			proof: &ProvenState,
+			asset_proof: &ProvenState,
+		) -> Result<(), Error<T>> {
+			let anchor =
+				Self::proven_target_header(domain, proof)?.ok_or(Error::<T>::NoTargetHeader)?;
+			ensure!(asset_proof.header.hash() == anchor, Error::<T>::UnanchoredProof);
+			Ok(())
+		}
 
+		/// Checks that a read proof from the target chain of a domain was taken on the chain
+		/// `asset` lives on.
+		///
+		/// Assets identified by domain are checked on the target chain of that same domain, so
+		/// only genesis hashes have to be proven.
+		fn check_target_chain(asset: &AssetId, proof: &ProvenState) -> Result<(), Error<T>> {
+			if let ChainId::Genesis(genesis_hash) = &asset.chain {
+				let value = proof
+					.read_value(&genesis_hash_storage_key())
+					.map_err(|_| Error::<T>::InvalidProof)?;
+				let proven = value.and_then(|value| H256::decode(&mut &value[..]).ok());
+				ensure!(proven == Some(*genesis_hash), Error::<T>::ChainMismatch);
+			}
 			Ok(())
 		}
 
-		#[pallet::call_index(2)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
-		pub fn cleanup_revoked_domains(
+		/// Checks a read proof from the target chain of a domain and returns whether `asset`
+		/// exists there.
+		///
+		/// The proof must be anchored with [`Self::check_anchored`] first.
+		fn check_asset_proof(asset: &AssetId, proof: &ProvenState) -> Result<bool, Error<T>> {
+			Self::check_target_chain(asset, proof)?;
+			let assets_prefix = Self::assets_module_prefix();
+			let value = proof
+				.read_value(&asset_storage_key(
+					&assets_prefix,
+					ASSETS_STORAGE_PREFIX,
+					&asset.local_id,
+				))
+				.map_err(|_| Error::<T>::InvalidProof)?;
+			Ok(value.is_some())
+		}
+
+		/// Checks a read proof from the target chain of a domain and returns the description of
+		/// `asset` if it exists there.
+		///
+		/// The proof must be anchored with [`Self::check_anchored`] first.
+		fn proven_asset_info(
+			asset: &AssetId,
+			proof: &ProvenState,
+		) -> Result<Option<AssetInfo<T::AccountId, BlockNumberFor<T>>>, Error<T>> {
+			Self::check_target_chain(asset, proof)?;
+			let assets_prefix = Self::assets_module_prefix();
+			let details = proof
+				.read_value(&asset_storage_key(
+					&assets_prefix,
+					ASSETS_STORAGE_PREFIX,
+					&asset.local_id,
+				))
+				.map_err(|_| Error::<T>::InvalidProof)?;
+			let Some(details) = details else { return Ok(None) };
+			let details = RemoteAssetDetails::<T::AccountId>::decode(&mut &details[..])
+				.map_err(|_| Error::<T>::InvalidAssetInfo)?;
+
+			// Assets without metadata have none stored, which reads as empty metadata
+			let metadata = proof
+				.read_value(&asset_storage_key(
+					&assets_prefix,
+					ASSETS_METADATA_PREFIX,
+					&asset.local_id,
+				))
+				.map_err(|_| Error::<T>::InvalidProof)?
+				.map(|metadata| RemoteAssetMetadata::decode(&mut &metadata[..]))
+				.transpose()
+				.map_err(|_| Error::<T>::InvalidAssetInfo)?
+				.unwrap_or_default();
+
+			Ok(Some(AssetInfo {
+				name: metadata.name,
+				symbol: metadata.symbol,
+				decimals: metadata.decimals,
+				min_balance: details.min_balance,
+				owner: details.owner,
+				updated_at: <frame_system::Pallet<T>>::block_number(),
+			}))
+		}
+
+		/// Stores the description of `asset` on the target chain of `domain` and indexes it by
+		/// name.
+		fn set_asset_metadata(
+			asset: &[u8],
+			domain: &[u8],
+			info: AssetInfo<T::AccountId, BlockNumberFor<T>>,
+		) {
+			if let Some(previous) = AssetMetadata::<T>::get(asset, domain) {
+				AssetsByName::<T>::remove(&previous.name, (asset, domain));
+			}
+			AssetsByName::<T>::insert(&info.name, (asset, domain), ());
+			AssetMetadata::<T>::insert(asset, domain, info);
+			Self::deposit_event(Event::AssetMetadataUpdated(asset.to_vec(), domain.to_vec()));
+		}
+
+		fn remove_asset_metadata(asset: &[u8], domain: &[u8]) {
+			if let Some(previous) = AssetMetadata::<T>::take(asset, domain) {
+				AssetsByName::<T>::remove(&previous.name, (asset, domain));
+			}
+		}
+
+		/// The `(asset, domain)` pairs whose asset is named `name` on the target chain of the
+		/// domain.
+		pub fn assets_named(name: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+			AssetsByName::<T>::iter_key_prefix(name).collect()
+		}
+
+		/// Domains verified to provide the asset `asset_hash`.
+		pub fn providers_of(asset_hash: &[u8]) -> Vec<Vec<u8>> {
+			AssetProviders::<T>::iter_key_prefix(asset_hash).collect()
+		}
+
+		/// Assets `domain` was verified to provide.
+		pub fn assets_of(domain: &[u8]) -> Vec<Vec<u8>> {
+			ProviderAssets::<T>::iter_key_prefix(domain).collect()
+		}
+
+		/// Records `domain` as a provider of `asset` in both indexes.
+		///
+		/// Returns `false` without changing anything if it already was one.
+		fn add_provider(
+			asset: &[u8],
+			domain: &[u8],
+			info: ProviderInfo<BlockNumberFor<T>>,
+		) -> bool {
+			if AssetProviders::<T>::contains_key(asset, domain) {
+				// A verified request keeps the asset when it is dropped from the manifest
+				if info.request_id.is_some() {
+					AssetProviders::<T>::insert(asset, domain, &info);
+					ProviderAssets::<T>::insert(domain, asset, info);
+				}
+				return false;
+			}
+			AssetProviders::<T>::insert(asset, domain, &info);
+			ProviderAssets::<T>::insert(domain, asset, info);
+			ProviderCount::<T>::mutate(asset, |count| *count = count.saturating_add(1));
+			AssetCount::<T>::mutate(domain, |count| *count = count.saturating_add(1));
+			if !ProviderChecks::<T>::contains_key(domain) {
+				let now = <frame_system::Pallet<T>>::block_number();
+				let check = ProviderCheck { last_verified: now, last_attempted: now, failures: 0 };
+				ProviderChecks::<T>::insert(domain, check);
+			}
+			true
+		}
+
+		/// Removes `domain` from the providers of `asset` in both indexes, along with the
+		/// description of the asset on its target chain.
+		///
+		/// Returns `false` if it was not one.
+		fn remove_provider(asset: &[u8], domain: &[u8]) -> bool {
+			if AssetProviders::<T>::take(asset, domain).is_none() {
+				return false;
+			}
+			ProviderAssets::<T>::remove(domain, asset);
+			Self::remove_asset_metadata(asset, domain);
+			ProviderCount::<T>::mutate_exists(asset, |count| {
+				*count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0)
+			});
+			AssetCount::<T>::mutate_exists(domain, |count| {
+				*count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0)
+			});
+			if !AssetCount::<T>::contains_key(domain) {
+				ProviderChecks::<T>::remove(domain);
+			}
+			true
+		}
+
+		/// Lists the described `(asset, domain)` pairs whose asset name starts with `name_prefix`
+		/// in storage order after `start_after`.
+		///
+		/// Names are only indexed by their hash, so prefixes cannot be looked up and the
+		/// descriptions are scanned instead. At most `MAX_SEARCH_SCAN` of them are read per call,
+		/// and the pair to resume after is returned until the scan reaches the end of storage.
+		pub fn search_assets(
+			name_prefix: &[u8],
+			start_after: Option<(Vec<u8>, Vec<u8>)>,
+			limit: u32,
+		) -> (
+			Vec<(Vec<u8>, Vec<u8>, AssetInfo<T::AccountId, BlockNumberFor<T>>)>,
+			Option<(Vec<u8>, Vec<u8>)>,
+		) {
+			let mut entries = match start_after {
+				Some((asset, domain)) =>
+					AssetMetadata::<T>::iter_from(AssetMetadata::<T>::hashed_key_for(asset, domain)),
+				None => AssetMetadata::<T>::iter(),
+			};
+			let mut found = Vec::new();
+			let mut last = None;
+			for _ in 0..MAX_SEARCH_SCAN {
+				if found.len() >= limit as usize {
+					break;
+				}
+				let Some((asset, domain, info)) = entries.next() else { return (found, None) };
+				last = Some((asset.clone(), domain.clone()));
+				if info.name.starts_with(name_prefix) {
+					found.push((asset, domain, info));
+				}
+			}
+			(found, last)
+		}
+
+		/// Reads `asset` from the target chain of a registered domain with a storage proof at
+		/// `anchor`, the header committed by the domain.
+		///
+		/// The RPC endpoints registered for the domain are tried first, then the boot nodes of
+		/// its chain spec. The first node returning a valid proof is trusted.
+		///
+		/// Returns the proof along with why the request for the asset should be rejected, or
+		/// `None` if the asset exists.
+		fn query_target_chain(
+			domain_info: &pallet_tld::DomainInfo<T::AccountId>,
+			asset: &AssetId,
+			anchor: H256,
+		) -> Result<(Option<RejectionReason>, ReadProof), &'static str> {
+			let assets_prefix = Self::assets_module_prefix();
+			let keys = [
+				asset_storage_key(&assets_prefix, ASSETS_STORAGE_PREFIX, &asset.local_id),
+				asset_storage_key(&assets_prefix, ASSETS_METADATA_PREFIX, &asset.local_id),
+				genesis_hash_storage_key(),
+			];
+			for rpc_endpoint in Self::target_rpc_endpoints(domain_info)? {
+				let deadline = remote::deadline_in(Self::http_timeout_ms());
+				let proof = remote::read_proof(&rpc_endpoint, &keys, anchor, deadline);
+
+				match proof {
+					Ok(proof) => match Self::check_asset_proof(asset, &proof.state()) {
+						Ok(true) => return Ok((None, proof)),
+						Ok(false) => return Ok((Some(RejectionReason::AssetNotFound), proof)),
+						Err(Error::<T>::ChainMismatch) =>
+							return Ok((Some(RejectionReason::WrongChain), proof)),
+						Err(err) =>
+							log::error!("Rejecting proof returned by {}: {:?}", rpc_endpoint, err),
+					},
+					Err(err) => log::info!(
+						"Target node {} failed, trying the next one: {}",
+						rpc_endpoint,
+						err
+					),
+				}
+			}
+
+			Err("No node of the target chain could be queried")
+		}
+
+		/// RPC endpoints of the target chain of a domain, falling back on the boot nodes of its
+		/// chain spec when it registered none.
+		fn target_rpc_endpoints(
+			domain_info: &pallet_tld::DomainInfo<T::AccountId>,
+		) -> Result<Vec<String>, &'static str> {
+			let rpc_endpoints: Vec<String> = domain_info
+				.rpc_endpoints
+				.iter()
+				.filter_map(|endpoint| multiaddr::parse_rpc_endpoint(endpoint))
+				.map(String::from)
+				.collect();
+			if !rpc_endpoints.is_empty() {
+				return Ok(rpc_endpoints);
+			}
+
+			let spec_url = str::from_utf8(&domain_info.chain_spec).map_err(|_| "Invalid UTF-8")?;
+			let spec = fetch_json_from_url(spec_url, Self::http_timeout_ms())?;
+			Ok(multiaddr::boot_node_endpoints(&spec.boot_nodes))
+		}
+
+		/// Reads all the assets listed in a manifest from the target chain of a domain, with a
+		/// single storage proof at `anchor`, the header committed by the domain.
+		fn query_manifest_assets(
+			domain_info: &pallet_tld::DomainInfo<T::AccountId>,
+			assets: &[AssetId],
+			anchor: H256,
+		) -> Result<ReadProof, &'static str> {
+			let assets_prefix = Self::assets_module_prefix();
+			let mut keys = vec![genesis_hash_storage_key()];
+			for asset in assets {
+				keys.push(asset_storage_key(
+					&assets_prefix,
+					ASSETS_STORAGE_PREFIX,
+					&asset.local_id,
+				));
+				keys.push(asset_storage_key(
+					&assets_prefix,
+					ASSETS_METADATA_PREFIX,
+					&asset.local_id,
+				));
+			}
+
+			for rpc_endpoint in Self::target_rpc_endpoints(domain_info)? {
+				let deadline = remote::deadline_in(Self::http_timeout_ms());
+				let proof = remote::read_proof(&rpc_endpoint, &keys, anchor, deadline);
+				match proof {
+					Ok(proof) => {
+						let state = proof.state();
+						if keys.iter().all(|key| state.read_value(key).is_ok()) {
+							return Ok(proof);
+						}
+						log::error!("Rejecting proof returned by {}", rpc_endpoint);
+					},
+					Err(err) => log::info!(
+						"Target node {} failed, trying the next one: {}",
+						rpc_endpoint,
+						err
+					),
+				}
+			}
+
+			Err("No node of the target chain could be queried")
+		}
+
+		/// Reads `domain` from a single TLD node with a storage proof at a finalized block.
+		///
+		/// The proof also covers the given records about the domain.
+		fn query_tld_node(
+			rpc_endpoint: &str,
+			tld: &[u8],
+			domain: &[u8],
+			records: &[TldRecord],
+		) -> Result<(bool, ReadProof), &'static str> {
+			let deadline = remote::deadline_in(Self::http_timeout_ms());
+			Self::refresh_tld_layout(rpc_endpoint, tld, deadline);
+
+			// Never trust raw storage values, only values proven against the header tracked by the
+			// light client so that the runtime can check it too.
+			let (_, block_hash) =
+				pallet_lightclient::Pallet::<T::PalletLightClient>::best_finalized(tld)
+					.ok_or("TLD chain is not followed by the light client")?;
+			let layout = Self::tld_layout(tld);
+			let mut keys = vec![layout.storage_key(domain)];
+			keys.extend(records.iter().map(|record| record.storage_key(&layout, domain)));
+			let proof = remote::read_proof(rpc_endpoint, &keys, block_hash, deadline)?;
+			let registered = Self::check_domain_proof(domain, &proof.state()).map_err(|err| {
+				log::error!("Rejecting proof returned by {}: {:?}", rpc_endpoint, err);
+				"Invalid proof returned by TLD node"
+			})?;
+
+			Ok((registered, proof))
+		}
+
+		/// Fetches the runtime version of the TLD chain behind `rpc_endpoint` at block `at` and the
+		/// layout of its domain map, reusing the cached layout if the runtime was not upgraded
+		/// since.
+		fn derive_tld_layout(
+			rpc_endpoint: &str,
+			cached: Option<LayoutCache>,
+			at: H256,
+			deadline: Timestamp,
+		) -> Result<(u32, DomainMapLayout), &'static str> {
+			let spec_version = remote::spec_version(rpc_endpoint, at, deadline)?;
+			if let Some(cached) = cached.filter(|cached| cached.spec_version == spec_version) {
+				return Ok((spec_version, cached.layout));
+			}
+
+			let default = Self::default_tld_layout();
+			let runtime_metadata = remote::metadata(rpc_endpoint, at, deadline)?;
+			let layout = metadata::layout_from_metadata(
+				&runtime_metadata,
+				&default.storage_prefix,
+				&default.pallet_prefix,
+			)?;
+			Ok((spec_version, layout))
+		}
+
+		/// Checks once per cadence whether the runtime of the TLD chain `tld` was upgraded and, if
+		/// so, derives the layout of its domain map from its metadata again.
+		///
+		/// Layouts are cached in offchain storage by spec version, and only submitted when they
+		/// differ from the one the runtime uses. The runtime is read at the best header finalized
+		/// by the light client, whose runtime code is proven along with the layout.
+		fn refresh_tld_layout(rpc_endpoint: &str, tld: &[u8], deadline: Timestamp) {
+			let cache_key = [LAYOUT_CACHE_PREFIX, tld].concat();
+			let cache = StorageValueRef::persistent(&cache_key);
+			let cached = cache.get::<LayoutCache>().ok().flatten();
+
+			let current_block: u32 = <frame_system::Pallet<T>>::block_number().saturated_into();
+			if let Some(cached) = &cached {
+				if current_block < cached.checked_at.saturating_add(Self::ocw_cadence()) {
+					return;
+				}
+			}
+
+			let Some((_, block_hash)) =
+				pallet_lightclient::Pallet::<T::PalletLightClient>::best_finalized(tld)
+			else {
+				return;
+			};
+			let (spec_version, layout) =
+				match Self::derive_tld_layout(rpc_endpoint, cached, block_hash, deadline) {
+					Ok(layout) => layout,
+					Err(err) => {
+						log::info!("Could not derive the domain map layout of the TLD: {}", err);
+						return;
+					},
+				};
+			cache.set(&LayoutCache {
+				spec_version,
+				layout: layout.clone(),
+				checked_at: current_block,
+			});
+
+			if layout != Self::tld_layout(tld) {
+				let code_key = well_known_keys::CODE;
+				let proof =
+					remote::read_proof(rpc_endpoint, &[code_key.to_vec()], block_hash, deadline)
+						.and_then(|proof| proof.without_value(code_key));
+				let proof = match proof {
+					Ok(proof) => proof,
+					Err(err) => {
+						log::info!("Could not prove the runtime code of the TLD: {}", err);
+						return;
+					},
+				};
+				let data = TldLayout { tld: tld.to_vec(), layout, proof };
+				Self::submit_signed_payload(data, |payload, signature| Call::set_tld_layout {
+					payload,
+					signature,
+				});
+			}
+		}
+
+		/// Queries `rpc_endpoint` and records its answer in `answers`, which holds the proofs
+		/// backing each answer indexed by whether the domain is registered.
+		///
+		/// Returns the answer once [`Pallet::tld_query_quorum`] nodes agree on it.
+		fn collect_tld_answer(
+			rpc_endpoint: &str,
+			tld: &[u8],
+			domain: &[u8],
+			records: &[TldRecord],
+			answers: &mut [Vec<ReadProof>; 2],
+		) -> Option<(bool, ReadProof)> {
+			let (registered, proof) = match Self::query_tld_node(rpc_endpoint, tld, domain, records)
+			{
+				Ok(answer) => answer,
+				Err(err) => {
+					log::info!("TLD node {} failed, trying the next one: {}", rpc_endpoint, err);
+					return None;
+				},
+			};
+
+			let proofs = &mut answers[registered as usize];
+			proofs.push(proof);
+			if proofs.len() >= Self::tld_query_quorum() as usize {
+				return Some((registered, proofs.swap_remove(0)));
+			}
+			None
+		}
+
+		/// Reads `domain` from the nodes of its TLD chain, in order, until
+		/// [`Pallet::tld_query_quorum`] of them agree on whether it is registered.
+		///
+		/// The RPC endpoints registered for the TLD are queried first. The boot nodes of its chain
+		/// spec are only used as a fallback, since their RPC port has to be guessed.
+		///
+		/// Returns the agreed answer along with one of the verified proofs, or an error if not
+		/// enough TLD nodes could be queried. Proofs also cover the given records about the
+		/// domain.
+		fn query_tld_network(
+			domain: Vec<u8>,
+			records: &[TldRecord],
+		) -> Result<(bool, ReadProof), &'static str> {
+			let tld = extract_tld(&domain).ok_or("Domain has no TLD")?;
+
+			let tld_info = pallet_rootdns::Pallet::<T::PalletRootDNS>::get_chainspec_for_tld(tld)
+				.ok_or("TLD not registered")?;
+
+			let mut answers: [Vec<ReadProof>; 2] = [Vec::new(), Vec::new()];
+
+			for endpoint in tld_info.rpc_endpoints.iter() {
+				let Some(rpc_endpoint) = multiaddr::parse_rpc_endpoint(endpoint) else {
+					log::info!("Skipping malformed RPC endpoint registered for the TLD");
+					continue;
+				};
+				if let Some(answer) =
+					Self::collect_tld_answer(rpc_endpoint, tld, &domain, records, &mut answers)
+				{
+					return Ok(answer);
+				}
+			}
+
+			let spec_url = str::from_utf8(&tld_info.chain_spec).map_err(|_| "Invalid UTF-8")?;
+			let spec = fetch_json_from_url(spec_url, Self::http_timeout_ms())?;
+
+			let boot_node_endpoints = multiaddr::boot_node_endpoints(&spec.boot_nodes);
+			if boot_node_endpoints.is_empty() && tld_info.rpc_endpoints.is_empty() {
+				log::error!("No usable boot nodes found in chainspec");
+				return Err("No usable boot nodes found in chainspec");
+			}
+
+			for rpc_endpoint in boot_node_endpoints {
+				if let Some(answer) =
+					Self::collect_tld_answer(&rpc_endpoint, tld, &domain, records, &mut answers)
+				{
+					return Ok(answer);
+				}
+			}
+
+			Err("Not enough TLD nodes agreed on the domain")
+		}
+
+		fn submit_verification_start(request_ids: Vec<RequestId>) {
+			let data = VerificationStart { request_ids };
+			Self::submit_signed_payload(data, |payload, signature| Call::begin_verification {
+				payload,
+				signature,
+			});
+		}
+
+		fn submit_domain_verification(
+			request_id: RequestId,
+			proof: ReadProof,
+			asset_proof: ReadProof,
+		) {
+			let data = DomainVerification { request_id, proof, asset_proof };
+			Self::submit_signed_payload(data, |payload, signature| Call::submit_verified_domain {
+				payload,
+				signature,
+			});
+		}
+
+		fn submit_request_rejection(
+			request_id: RequestId,
+			proof: ReadProof,
+			asset_proof: Option<ReadProof>,
+		) {
+			let data = RequestRejection { request_id, proof, asset_proof };
+			Self::submit_signed_payload(data, |payload, signature| Call::reject_pending_request {
+				payload,
+				signature,
+			});
+		}
+
+		/// Checks that the request was authorized by the owner or a manager of the registered
+		/// domain and that the requested asset exists on its target chain, then submits the
+		/// verification or the rejection of the request.
+		fn verify_requested_asset(
+			request_id: RequestId,
+			request: AssetRequest<T::AccountId>,
+			proof: ReadProof,
+		) {
+			let state = proof.state();
+			let domain_info = match Self::proven_domain_info(&request.domain, &state) {
+				Ok(Some(domain_info)) => domain_info,
+				_ => return,
+			};
+			match Self::is_authorized(&request.domain, &request.authorizer, &domain_info, &state) {
+				Ok(true) => {},
+				// No asset proof is needed to reject an unauthorized request
+				Ok(false) => return Self::submit_request_rejection(request_id, proof, None),
+				Err(err) => {
+					log::info!("Could not check the authorization: {:?}", err);
+					return;
+				},
+			}
+
+			let anchor = match Self::proven_target_header(&request.domain, &state) {
+				Ok(Some(anchor)) => anchor,
+				// No asset proof is needed to reject a request that cannot be anchored
+				Ok(None) => return Self::submit_request_rejection(request_id, proof, None),
+				Err(err) => {
+					log::info!("Could not read the target header: {:?}", err);
+					return;
+				},
+			};
+
+			match Self::query_target_chain(&domain_info, &request.asset, anchor) {
+				Ok((None, asset_proof)) =>
+					Self::submit_domain_verification(request_id, proof, asset_proof),
+				Ok((Some(_), asset_proof)) =>
+					Self::submit_request_rejection(request_id, proof, Some(asset_proof)),
+				Err(err) => log::info!("Could not check the requested asset: {}", err),
+			}
+		}
+
+		/// Checks the asset manifests of the tracked domains checked the longest time ago, and
+		/// submits the ones that changed along with a proof of the assets they list.
+		fn ingest_manifests(batch_size: usize) {
+			let mut tracked: Vec<(BlockNumberFor<T>, Vec<u8>, Option<H256>)> =
+				ManifestStates::<T>::iter()
+					.map(|(domain, state)| (state.checked_at, domain, state.content_hash))
+					.collect();
+			tracked.sort();
+
+			for (_, domain, ingested_hash) in tracked.into_iter().take(batch_size) {
+				let records = [TldRecord::Manifest, TldRecord::TargetHeader];
+				let proof = match Self::query_tld_network(domain.clone(), &records) {
+					Ok((_, proof)) => proof,
+					Err(err) => {
+						log::info!("Could not read the asset manifest record: {}", err);
+						continue;
+					},
+				};
+
+				let state = proof.state();
+				let domain_info = Self::proven_domain_info(&domain, &state).ok().flatten();
+				let manifest = match &domain_info {
+					Some(_) => Self::proven_manifest(&domain, &state).ok().flatten(),
+					None => None,
+				};
+				let (assets, asset_proof) = match (domain_info, manifest) {
+					(Some(domain_info), Some(manifest))
+						if Some(manifest.content_hash) != ingested_hash =>
+						match Self::fetch_manifest(&domain, &domain_info, &manifest, &state) {
+							Ok((assets, asset_proof)) => (assets, Some(asset_proof)),
+							Err(err) => {
+								log::info!("Could not ingest the asset manifest: {}", err);
+								continue;
+							},
+						},
+					// Unchanged and missing manifests are proven by the TLD chain alone
+					_ => (Vec::new(), None),
+				};
+
+				let existing_assets = AssetCount::<T>::get(&domain);
+				let data =
+					ManifestIngestion { domain, proof, assets, asset_proof, existing_assets };
+				Self::submit_signed_payload(data, |payload, signature| Call::ingest_manifest {
+					payload,
+					signature,
+				});
+			}
+		}
+
+		/// Downloads an asset manifest, checks it against its content hash and reads the assets
+		/// it lists from the target chain of the domain, at the header committed in `proof`.
+		fn fetch_manifest(
+			domain: &[u8],
+			domain_info: &pallet_tld::DomainInfo<T::AccountId>,
+			manifest: &pallet_tld::AssetManifest,
+			proof: &ProvenState,
+		) -> Result<(Vec<AssetId>, ReadProof), &'static str> {
+			let anchor = Self::proven_target_header(domain, proof)
+				.map_err(|_| "Invalid target header record")?
+				.ok_or("No target header committed by the domain")?;
+
+			let url = str::from_utf8(&manifest.url).map_err(|_| "Invalid UTF-8")?;
+			let deadline = remote::deadline_in(Self::http_timeout_ms());
+			let content = remote::fetch_file(url, MAX_MANIFEST_LEN, deadline)?;
+			if H256(sp_core::hashing::blake2_256(&content)) != manifest.content_hash {
+				return Err("Manifest does not match its content hash");
+			}
+
+			let assets = Vec::<AssetId>::decode_all(&mut &content[..])
+				.map_err(|_| "Invalid manifest content")?;
+			if assets.len() > MAX_MANIFEST_ENTRIES {
+				return Err("Manifest lists too many assets");
+			}
+
+			let asset_proof = Self::query_manifest_assets(domain_info, &assets, anchor)?;
+			Ok((assets, asset_proof))
+		}
+
+		/// Registers `domain` as a provider of the assets listed in its manifest that exist on
+		/// its target chain, and deregisters the ones previously listed that are not anymore.
+		///
+		/// Entries that are malformed or cannot be verified are skipped. Returns the number of
+		/// registered and deregistered assets.
+		fn apply_manifest(
+			domain: &[u8],
+			assets: Vec<AssetId>,
+			asset_proof: Option<ProvenState>,
+		) -> Result<(u32, u32), Error<T>> {
+			let current_block_number = <frame_system::Pallet<T>>::block_number();
+			let mut listed: Vec<Vec<u8>> = Vec::new();
+			let mut added = 0u32;
+
+			if !assets.is_empty() {
+				let asset_proof = asset_proof.ok_or(Error::<T>::MissingAssetProof)?;
+				for asset in assets {
+					if !Self::manifest_entry_valid(domain, &asset) {
+						continue;
+					}
+					let asset_info = match Self::proven_asset_info(&asset, &asset_proof) {
+						Ok(Some(asset_info)) => asset_info,
+						Ok(None) | Err(Error::<T>::ChainMismatch) => continue,
+						Err(err) => return Err(err),
+					};
+
+					let asset_hash = asset.canonical_hash().as_bytes().to_vec();
+					let info = ProviderInfo { request_id: None, verified_at: current_block_number };
+					if Self::add_provider(&asset_hash, domain, info) {
+						added = added.saturating_add(1);
+						Self::deposit_event(Event::AssetRegisteredForDomain(
+							asset_hash.clone(),
+							domain.to_vec(),
+							current_block_number.into(),
+						));
+					}
+					// The first registered id is kept, so that later providers cannot replace
+					// its XCM location
+					if !AssetIds::<T>::contains_key(&asset_hash) {
+						AssetIds::<T>::insert(&asset_hash, &asset);
+					}
+					Self::set_asset_metadata(&asset_hash, domain, asset_info);
+					listed.push(asset_hash);
+				}
+			}
+
+			listed.sort();
+			let dropped: Vec<Vec<u8>> = ProviderAssets::<T>::iter_prefix(domain)
+				.filter(|(asset, info)| {
+					info.request_id.is_none() && listed.binary_search(asset).is_err()
+				})
+				.map(|(asset, _)| asset)
+				.collect();
+			let mut removed = 0u32;
+			for asset in dropped {
+				if Self::remove_provider(&asset, domain) {
+					removed = removed.saturating_add(1);
+				}
+			}
+
+			Ok((added, removed))
+		}
+
+		/// Whether a manifest entry can be registered for `domain`, following the checks of
+		/// `register_asset_for_domain`.
+		fn manifest_entry_valid(domain: &[u8], asset: &AssetId) -> bool {
+			if asset.validate().is_err() || asset.standard != AssetStandard::PalletAssets {
+				return false;
+			}
+			match &asset.chain {
+				ChainId::Domain(chain_domain) => chain_domain == domain,
+				ChainId::Genesis(_) => true,
+			}
+		}
+
+		/// Providing domains to revalidate in this run, most due first.
+		///
+		/// Healthy providers are due in proportion to the time since they were last verified,
+		/// and those not verified within the revalidation window come first. Providers whose TLD
+		/// chain could not be reached are due in proportion to the time since they were last
+		/// attempted, doubled for each recent failure.
+		///
+		/// The batch grows beyond `batch_size` when needed to get through all providers within
+		/// the window, and healthy providers keep at least their share of it, so that providers
+		/// failing over and over cannot hold them back.
+		fn get_domain_batch(batch_size: usize) -> Vec<Vec<u8>> {
+			let current_block: u32 = <frame_system::Pallet<T>>::block_number().saturated_into();
+			let window = Self::revalidation_window().max(1);
+			let age = |block: BlockNumberFor<T>| {
+				u64::from(current_block.saturating_sub(block.saturated_into()))
+			};
+
+			let mut healthy: Vec<(bool, u64, Vec<u8>)> = Vec::new();
+			let mut failing: Vec<(u64, Vec<u8>)> = Vec::new();
+			for (domain, check) in ProviderChecks::<T>::iter() {
+				if check.failures == 0 {
+					let since_verified = age(check.last_verified);
+					healthy.push((since_verified >= u64::from(window), since_verified, domain));
+				} else {
+					let weight = 1u64 << check.failures.min(MAX_FAILURE_WEIGHT);
+					failing.push((age(check.last_attempted) * weight, domain));
+				}
+			}
+			healthy.sort_by(|a, b| (b.0, b.1).cmp(&(a.0, a.1)));
+			failing.sort_by(|a, b| b.0.cmp(&a.0));
+
+			// Each run covers its share of the providers, `OcwCadence` blocks out of the window
+			let share_of = |providers: usize| {
+				(providers as u64 * u64::from(Self::ocw_cadence())).div_ceil(u64::from(window))
+					as usize
+			};
+			let total = batch_size.max(share_of(healthy.len() + failing.len()));
+			let reserved = share_of(healthy.len()).max(total / 2).min(healthy.len());
+			let retried = total.saturating_sub(reserved).min(failing.len());
+
+			healthy
+				.into_iter()
+				.take(total - retried)
+				.map(|(_, _, domain)| domain)
+				.chain(failing.into_iter().take(retried).map(|(_, domain)| domain))
+				.collect()
+		}
+
+		/// Reads the assets provided by the registered `domain` from its target chain again and
+		/// submits their current descriptions, starting from the least recently refreshed ones.
+		fn refresh_asset_metadata(domain: Vec<u8>, proof: ReadProof) {
+			let state = proof.state();
+			let domain_info = match Self::proven_domain_info(&domain, &state) {
+				Ok(Some(domain_info)) => domain_info,
+				_ => return,
+			};
+			// Without a committed header only the revalidation itself is submitted
+			let anchor = Self::proven_target_header(&domain, &state).ok().flatten();
+
+			// Assets without a description come first, then the ones proven the longest ago
+			let mut provided: Vec<_> = ProviderAssets::<T>::iter_key_prefix(&domain)
+				.map(|asset| {
+					let updated_at =
+						AssetMetadata::<T>::get(&asset, &domain).map(|info| info.updated_at);
+					(updated_at, asset)
+				})
+				.collect();
+			provided.sort();
+
+			let mut assets = Vec::new();
+			for (_, asset) in provided.into_iter().take(MAX_REFRESHED_ASSETS) {
+				let Some(anchor) = anchor else { break };
+				let Some(asset_id) = AssetIds::<T>::get(&asset) else { continue };
+				match Self::query_target_chain(&domain_info, &asset_id, anchor) {
+					Ok((None, asset_proof)) => assets.push((asset, asset_proof)),
+					Ok((Some(reason), _)) => log::info!(
+						"Provided asset no longer found on the target chain: {:?}",
+						reason
+					),
+					Err(err) => log::info!("Could not refresh the asset metadata: {}", err),
+				}
+			}
+
+			// Submitted even without assets, as it records that the domain was revalidated
+			let data = AssetMetadataRefresh { domain, proof, assets };
+			Self::submit_signed_payload(data, |payload, signature| Call::refresh_asset_metadata {
+				payload,
+				signature,
+			});
+		}
+
+		fn remove_revoked_domains(domain_batch: Vec<Vec<u8>>) {
+			let mut revoked_domains = Vec::new();
+			let mut failed_domains = Vec::new();
+
+			for domain in domain_batch {
+				// Only revoke domains proven to be unregistered, not ones we failed to query.
+				match Self::query_tld_network(domain.clone(), &[TldRecord::TargetHeader]) {
+					Ok((false, proof)) => revoked_domains.push((domain, proof)),
+					Ok((true, proof)) => Self::refresh_asset_metadata(domain, proof),
+					Err(err) => {
+						log::info!("Could not revalidate domain: {}", err);
+						failed_domains.push(domain);
+					},
+				}
+			}
+
+			if !failed_domains.is_empty() {
+				let data = RevalidationFailures { domains: failed_domains };
+				Self::submit_signed_payload(data, |payload, signature| {
+					Call::report_revalidation_failures { payload, signature }
+				});
+			}
+
+			if revoked_domains.is_empty() {
+				return;
+			}
+
+			let removed_assets = revoked_domains
+				.iter()
+				.map(|(domain, _)| AssetCount::<T>::get(domain))
+				.fold(0u32, u32::saturating_add);
+			let data = RevokedDomains { domains: revoked_domains, removed_assets };
+			Self::submit_signed_payload(data, |payload, signature| Call::cleanup_revoked_domains {
+				payload,
+				signature,
+			});
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			Self::expire_requests(now, remaining_weight)
+		}
+
+		fn offchain_worker(block_number: BlockNumberFor<T>) {
+			let current_time = block_number.into();
+			let batch_size = Self::domain_batch_size() as usize;
+			if Self::is_cadence_block(current_time) {
+				Self::remove_revoked_domains(Self::get_domain_batch(batch_size));
+				Self::ingest_manifests(batch_size);
+			}
+
+			let open_requests = Self::fetch_open_requests(batch_size, current_time);
+
+			let picked_up: Vec<RequestId> = open_requests
+				.iter()
+				.filter(|(_, request)| request.status == RequestStatus::Pending)
+				.map(|(request_id, _)| *request_id)
+				.collect();
+			if !picked_up.is_empty() {
+				Self::submit_verification_start(picked_up);
+			}
+
+			for (request_id, request) in open_requests {
+				// Failing to reach the TLD chain is retried until the request expires, anything
+				// proven is final.
+				let records =
+					[TldRecord::Manager(request.authorizer.encode()), TldRecord::TargetHeader];
+				match Self::query_tld_network(request.domain.clone(), &records) {
+					Ok((true, proof)) => Self::verify_requested_asset(request_id, request, proof),
+					Ok((false, proof)) => Self::submit_request_rejection(request_id, proof, None),
+					Err(err) => log::info!("Could not validate domain: {}", err),
+				}
+			}
+
+			for (request_id, withdrawal) in Self::fetch_pending_withdrawals(batch_size) {
+				let requester = TldRecord::Manager(withdrawal.requester.encode());
+				let withdrawn_assets = Self::withdrawn_assets(&withdrawal);
+				match Self::query_tld_network(withdrawal.domain, &[requester]) {
+					Ok((_, proof)) => {
+						let data = WithdrawalProof { request_id, proof, withdrawn_assets };
+						Self::submit_signed_payload(data, |payload, signature| {
+							Call::complete_withdrawal { payload, signature }
+						});
+					},
+					Err(err) => log::info!("Could not check withdrawal: {}", err),
+				}
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::call_index(0)]
+		#[pallet::weight(10_000 +
+			// Proof checks, `add_provider` and `set_asset_metadata`
+			T::DbWeight::get().reads_writes(12, 9).ref_time() +
+			proof_weight(&payload.data.proof, 3) +
+			proof_weight(&payload.data.asset_proof, 3) +
+			Pallet::<T>::attestation_weight().ref_time() +
+			Pallet::<T>::closing_weight().ref_time())]
+		pub fn submit_verified_domain(
 			origin: OriginFor<T>,
-			domains: Vec<Vec<u8>>,
+			payload: OcwPayloadOf<T, DomainVerification>,
+			// Checked in `validate_unsigned`
+			_signature: T::Signature,
 		) -> DispatchResult {
 			// Only allow off-chain workers to call this function
 			ensure_none(origin)?;
+			let authority = payload.public.into_account();
+			let DomainVerification { request_id, proof, asset_proof } = payload.data;
+			let (proof, asset_proof) = (proof.state(), asset_proof.state());
+			let pending_request = Self::open_request(request_id)?;
+
+			let domain_info = Self::proven_domain_info(&pending_request.domain, &proof)?
+				.ok_or(Error::<T>::DomainInvalid)?;
+			ensure!(
+				Self::is_authorized(
+					&pending_request.domain,
+					&pending_request.authorizer,
+					&domain_info,
+					&proof,
+				)?,
+				Error::<T>::NotAuthorized
+			);
+			Self::check_anchored(&pending_request.domain, &proof, &asset_proof)?;
+			let asset_info = Self::proven_asset_info(&pending_request.asset, &asset_proof)?
+				.ok_or(Error::<T>::AssetNotFound)?;
+
+			let subject = AttestationSubject::Request(request_id);
+			if !Self::attest(subject, authority, AttestationOutcome::Verified) {
+				return Ok(());
+			}
+
+			let current_block_number = <frame_system::Pallet<T>>::block_number();
+
+			// Verifying an existing provider again only refreshes the asset description
+			let info =
+				ProviderInfo { request_id: Some(request_id), verified_at: current_block_number };
+			if Self::add_provider(&pending_request.asset_hash, &pending_request.domain, info) {
+				Self::deposit_event(Event::AssetRegisteredForDomain(
+					pending_request.asset_hash.clone(),
+					pending_request.domain.clone(),
+					current_block_number.into(),
+				));
+			}
+			if !AssetIds::<T>::contains_key(&pending_request.asset_hash) {
+				AssetIds::<T>::insert(&pending_request.asset_hash, &pending_request.asset);
+			}
+			Self::set_asset_metadata(
+				&pending_request.asset_hash,
+				&pending_request.domain,
+				asset_info,
+			);
+			Self::set_request_status(request_id, pending_request, RequestStatus::Verified);
+
+			Ok(())
+		}
+
+		#[pallet::call_index(1)]
+		#[pallet::weight(10_000 +
+			T::DbWeight::get().reads_writes(4, 7).ref_time() +
+			authorization.as_ref().map_or(0, |_| SIGNATURE_WEIGHT))]
+		pub fn register_asset_for_domain(
+			origin: OriginFor<T>,
+			domain: Vec<u8>,
+			asset: AssetId,
+			authorization: Option<
+				OwnerAuthorization<T::AccountId, BlockNumberFor<T>, T::OwnerSignature>,
+			>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
-			for domain in domains {
-				let asset_list = ProviderAssets::<T>::get(&domain).unwrap();
-				for asset in asset_list.assets {
-					let provider_list = AssetProviders::<T>::get(&asset).unwrap();
-					let new_providers =
-						provider_list.providers.iter().filter(|&x| x != &domain).cloned().collect();
-					AssetProviders::<T>::insert(
-						asset.clone(),
-						ProviderList { providers: new_providers },
+			asset.validate().map_err(|_| Error::<T>::InvalidAssetId)?;
+			// Only `pallet_assets` assets can be proven to exist by the offchain worker
+			ensure!(asset.standard == AssetStandard::PalletAssets, Error::<T>::UnsupportedStandard);
+			// The asset is looked up on the target chain of the domain
+			if let ChainId::Domain(chain_domain) = &asset.chain {
+				ensure!(chain_domain == &domain, Error::<T>::ChainMismatch);
+			}
+
+			let current_block_number = <frame_system::Pallet<T>>::block_number();
+			let expiry_block = current_block_number.saturating_add(Self::request_lifetime().into());
+
+			// Without a signed authorization the requester must own or manage the domain itself
+			let asset_hash = asset.canonical_hash().as_bytes().to_vec();
+			let authorizer = match authorization {
+				Some(OwnerAuthorization { authorizer, expires_at, signature }) => {
+					// Expiring keeps the requester from registering the asset again with the
+					// same authorization once it is withdrawn
+					ensure!(expires_at >= current_block_number, Error::<T>::AuthorizationExpired);
+					let message =
+						(AUTHORIZATION_PREFIX, &domain, &asset_hash, &who, expires_at).encode();
+					ensure!(
+						signature.verify(&message[..], &authorizer),
+						Error::<T>::InvalidAuthorization
 					);
+					authorizer
+				},
+				None => who.clone(),
+			};
+
+			let request = AssetRequest {
+				requester: who.clone(),
+				authorizer,
+				domain,
+				asset_hash,
+				asset,
+				expires_at: expiry_block.into(),
+				status: RequestStatus::Pending,
+			};
+
+			let request_id = Self::take_request_id()?;
+			Self::reserve_deposit(&who, request_id)?;
+
+			// Request ocw to validate domain
+			Requests::<T>::insert(request_id, request.clone());
+			RequesterRequests::<T>::insert(&who, request_id, ());
+			RequestExpiries::<T>::insert(expiry_block, request_id, ());
+
+			// Emit event
+			Self::deposit_event(Event::DomainValidationRequested(who, request_id, request));
+
+			Ok(())
+		}
+
+		#[pallet::call_index(2)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(
+			6 * payload.data.domains.len() as u64 + 6 * payload.data.removed_assets as u64,
+			7 * payload.data.removed_assets as u64,
+		).ref_time() +
+			payload.data.domains.len() as u64 * Pallet::<T>::attestation_weight().ref_time() +
+			payload.data.domains.iter().map(|(_, proof)| proof_weight(proof, 1)).sum::<u64>())]
+		pub fn cleanup_revoked_domains(
+			origin: OriginFor<T>,
+			payload: OcwPayloadOf<T, RevokedDomains>,
+			// Checked in `validate_unsigned`
+			_signature: T::Signature,
+		) -> DispatchResult {
+			// Only allow off-chain workers to call this function
+			ensure_none(origin)?;
+			let authority = payload.public.into_account();
+			let RevokedDomains { domains, removed_assets } = payload.data;
+			// The weight covers deregistering up to `removed_assets` assets
+			let provided = domains
+				.iter()
+				.map(|(domain, _)| AssetCount::<T>::get(domain))
+				.fold(0u32, u32::saturating_add);
+			ensure!(provided <= removed_assets, Error::<T>::InvalidAssetCount);
+
+			for (domain, proof) in domains {
+				// Skip domains that the proof shows are still registered, and the ones whose proof
+				// is invalid without failing the whole batch
+				match Self::check_domain_proof(&domain, &proof.state()) {
+					Ok(true) => continue,
+					Ok(false) => {},
+					Err(err) => {
+						Self::deposit_event(Event::RevocationProofRejected(domain, err.into()));
+						continue;
+					},
+				}
+
+				// Domains revoked earlier have nothing left to remove
+				let assets: Vec<Vec<u8>> = ProviderAssets::<T>::iter_key_prefix(&domain).collect();
+				if assets.is_empty() {
+					continue;
+				}
+				let subject = AttestationSubject::Revocation(domain.clone());
+				if !Self::attest(subject, authority.clone(), AttestationOutcome::Revoked) {
+					continue;
+				}
+				for asset in assets {
+					Self::remove_provider(&asset, &domain);
 				}
-				ProviderAssets::<T>::remove(&domain);
 				Self::deposit_event(Event::AssetProviderRevoked(domain));
 			}
 
 			Ok(())
 		}
 
-		#[pallet::call_index(3)]
+		#[pallet::call_index(4)]
+		#[pallet::weight(10_000 +
+			T::DbWeight::get().writes(1).ref_time() +
+			Pallet::<T>::attestation_weight().ref_time() +
+			Pallet::<T>::closing_weight().ref_time())]
+		pub fn reject_pending_request(
+			origin: OriginFor<T>,
+			payload: OcwPayloadOf<T, RequestRejection>,
+			// Checked in `validate_unsigned`
+			_signature: T::Signature,
+		) -> DispatchResult {
+			// Only allow off-chain workers to call this function
+			ensure_none(origin)?;
+			let authority = payload.public.into_account();
+			let RequestRejection { request_id, proof, asset_proof } = payload.data;
+			let proof = proof.state();
+			let request = Self::open_request(request_id)?;
+
+			let reason = match Self::proven_domain_info(&request.domain, &proof)? {
+				None => RejectionReason::DomainNotRegistered,
+				// No asset proof is needed to reject an unauthorized request
+				Some(domain_info)
+					if !Self::is_authorized(
+						&request.domain,
+						&request.authorizer,
+						&domain_info,
+						&proof,
+					)? =>
+					RejectionReason::NotAuthorized,
+				Some(_) if Self::proven_target_header(&request.domain, &proof)?.is_none() =>
+					RejectionReason::NoTargetHeader,
+				Some(_) => {
+					let asset_proof = asset_proof.ok_or(Error::<T>::MissingAssetProof)?.state();
+					Self::check_anchored(&request.domain, &proof, &asset_proof)?;
+					match Self::check_asset_proof(&request.asset, &asset_proof) {
+						Ok(exists) => {
+							ensure!(!exists, Error::<T>::AssetExists);
+							RejectionReason::AssetNotFound
+						},
+						Err(Error::<T>::ChainMismatch) => RejectionReason::WrongChain,
+						Err(err) => return Err(err.into()),
+					}
+				},
+			};
+
+			let subject = AttestationSubject::Request(request_id);
+			if Self::attest(subject, authority, AttestationOutcome::Rejected(reason)) {
+				Self::set_request_status(request_id, request, RequestStatus::Rejected(reason));
+			}
+
+			Ok(())
+		}
+
+		#[pallet::call_index(5)]
 		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
-		pub fn remove_expired_pending_requests(
+		pub fn set_ocw_authorities(
 			origin: OriginFor<T>,
-			current_time: U256,
+			authorities: Vec<T::AccountId>,
 		) -> DispatchResult {
-			// // Only allow off-chain workers to call this function
+			T::AuthorityOrigin::ensure_origin(origin)?;
+			ensure!(
+				authorities.len() <= T::MaxAuthorities::get() as usize,
+				Error::<T>::TooManyAuthorities
+			);
+
+			OcwAuthorities::<T>::put(&authorities);
+
+			Self::deposit_event(Event::OcwAuthoritiesSet(authorities));
+
+			Ok(())
+		}
+
+		#[pallet::call_index(6)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(
+			1 + payload.data.request_ids.len() as u64,
+			payload.data.request_ids.len() as u64,
+		).ref_time())]
+		pub fn begin_verification(
+			origin: OriginFor<T>,
+			payload: OcwPayloadOf<T, VerificationStart>,
+			// Checked in `validate_unsigned`
+			_signature: T::Signature,
+		) -> DispatchResult {
+			// Only allow off-chain workers to call this function
 			ensure_none(origin)?;
+			ensure!(
+				payload.data.request_ids.len() <= Self::domain_batch_size() as usize,
+				Error::<T>::BatchTooLarge
+			);
+
+			for request_id in payload.data.request_ids {
+				match Requests::<T>::get(request_id) {
+					Some(request) if request.status == RequestStatus::Pending =>
+						Self::set_request_status(request_id, request, RequestStatus::Verifying),
+					_ => {},
+				}
+			}
 
-			let current_block_number = <frame_system::Pallet<T>>::block_number();
+			Ok(())
+		}
+
+		#[pallet::call_index(7)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn set_parameters(
+			origin: OriginFor<T>,
+			overrides: ParameterOverrides,
+		) -> DispatchResult {
+			T::ParametersOrigin::ensure_origin(origin)?;
 
-			for (account, data) in PendingRequests::<T>::iter() {
-				if current_block_number.into() < data.timestamp {
+			ensure!(
+				overrides.domain_batch_size != Some(0) &&
+					overrides.ocw_cadence != Some(0) &&
+					overrides.revalidation_window != Some(0) &&
+					overrides.attestation_threshold != Some(0) &&
+					overrides.max_pending_requests != Some(0) &&
+					overrides.tld_module_prefix.as_ref().map_or(true, |p| !p.is_empty()) &&
+					overrides.tld_storage_prefix.as_ref().map_or(true, |p| !p.is_empty()) &&
+					overrides.assets_module_prefix.as_ref().map_or(true, |p| !p.is_empty()) &&
+					overrides.tld_query_quorum != Some(0),
+				Error::<T>::InvalidParameter
+			);
+
+			Parameters::<T>::put(&overrides);
+
+			Self::deposit_event(Event::ParametersSet(overrides));
+
+			Ok(())
+		}
+
+		#[pallet::call_index(8)]
+		#[pallet::weight(10_000 +
+			T::DbWeight::get().reads_writes(2, 1).ref_time() +
+			proof_weight(&payload.data.proof, 1) +
+			Pallet::<T>::attestation_weight().ref_time())]
+		pub fn set_tld_layout(
+			origin: OriginFor<T>,
+			payload: OcwPayloadOf<T, TldLayout>,
+			// Checked in `validate_unsigned`
+			_signature: T::Signature,
+		) -> DispatchResult {
+			// Only allow off-chain workers to call this function
+			ensure_none(origin)?;
+			let authority = payload.public.into_account();
+			let TldLayout { tld, layout, proof } = payload.data;
+
+			ensure!(
+				!layout.pallet_prefix.is_empty() && !layout.storage_prefix.is_empty(),
+				Error::<T>::InvalidLayout
+			);
+
+			// The metadata the layout is derived from cannot be proven, so authorities only agree
+			// on a layout derived from the same runtime code, proven at a recent finalized header
+			let proof = proof.state();
+			Self::check_tld_header(&tld, &proof)?;
+			let code_hash = proof
+				.read_value_hash(well_known_keys::CODE)
+				.map_err(|_| Error::<T>::InvalidProof)?
+				.ok_or(Error::<T>::InvalidProof)?;
+			let outcome = AttestationOutcome::Layout(H256(sp_core::hashing::blake2_256(
+				&(code_hash, &layout).encode(),
+			)));
+			if !Self::attest(AttestationSubject::TldLayout(tld.clone()), authority, outcome) {
+				return Ok(());
+			}
+
+			TldLayouts::<T>::insert(&tld, &layout);
+
+			Self::deposit_event(Event::TldLayoutSet(tld, layout));
+
+			Ok(())
+		}
+
+		#[pallet::call_index(9)]
+		#[pallet::weight(10_000 +
+			T::DbWeight::get().reads_writes(
+				3 + 3 * payload.data.assets.len() as u64,
+				1 + 3 * payload.data.assets.len() as u64,
+			).ref_time() +
+			proof_weight(&payload.data.proof, 2) +
+			payload.data.assets.iter().map(|(_, proof)| proof_weight(proof, 2)).sum::<u64>() +
+			Pallet::<T>::attestation_weight().ref_time())]
+		pub fn refresh_asset_metadata(
+			origin: OriginFor<T>,
+			payload: OcwPayloadOf<T, AssetMetadataRefresh>,
+			// Checked in `validate_unsigned`
+			_signature: T::Signature,
+		) -> DispatchResult {
+			// Only allow off-chain workers to call this function
+			ensure_none(origin)?;
+			let authority = payload.public.into_account();
+			let AssetMetadataRefresh { domain, proof, assets } = payload.data;
+			ensure!(assets.len() <= MAX_REFRESHED_ASSETS, Error::<T>::TooManyAssets);
+			let proof = proof.state();
+
+			ensure!(Self::check_domain_proof(&domain, &proof)?, Error::<T>::DomainInvalid);
+
+			// Counts against the authorities attesting that the domain was revoked
+			let subject = AttestationSubject::Revocation(domain.clone());
+			if Attestations::<T>::iter_prefix(&subject).next().is_some() {
+				Self::attest(subject, authority, AttestationOutcome::Registered);
+			}
+
+			ProviderChecks::<T>::mutate(&domain, |check| {
+				if let Some(check) = check {
+					let now = <frame_system::Pallet<T>>::block_number();
+					check.last_verified = now;
+					check.last_attempted = now;
+					check.failures = 0;
+				}
+			});
+			Self::deposit_event(Event::ProviderRevalidated(domain.clone()));
+
+			let anchor = Self::proven_target_header(&domain, &proof)?;
+			for (asset, asset_proof) in assets {
+				ensure!(Some(asset_proof.header.hash()) == anchor, Error::<T>::UnanchoredProof);
+				// Only assets the domain was verified to provide are described
+				if !ProviderAssets::<T>::contains_key(&domain, &asset) {
 					continue;
 				}
-				PendingRequests::<T>::remove(account);
+				let Some(asset_id) = AssetIds::<T>::get(&asset) else { continue };
+				let asset_proof = asset_proof.state();
+				if let Some(asset_info) = Self::proven_asset_info(&asset_id, &asset_proof)? {
+					Self::set_asset_metadata(&asset, &domain, asset_info);
+				}
+			}
+
+			Ok(())
+		}
+
+		#[pallet::call_index(10)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(
+			payload.data.domains.len() as u64,
+			payload.data.domains.len() as u64,
+		).ref_time())]
+		pub fn report_revalidation_failures(
+			origin: OriginFor<T>,
+			payload: OcwPayloadOf<T, RevalidationFailures>,
+			// Checked in `validate_unsigned`
+			_signature: T::Signature,
+		) -> DispatchResult {
+			// Only allow off-chain workers to call this function
+			ensure_none(origin)?;
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			for domain in payload.data.domains {
+				let failures = ProviderChecks::<T>::mutate(&domain, |check| {
+					check.as_mut().map(|check| {
+						check.last_attempted = now;
+						check.failures = check.failures.saturating_add(1);
+						check.failures
+					})
+				});
+				if let Some(failures) = failures {
+					Self::deposit_event(Event::RevalidationFailed(domain, failures));
+				}
 			}
 
-			Self::deposit_event(Event::ExpiredRequestsRemoved);
+			Ok(())
+		}
+
+		#[pallet::call_index(11)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 3).ref_time())]
+		pub fn track_manifest(origin: OriginFor<T>, domain: Vec<u8>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(extract_tld(&domain).is_some(), Error::<T>::DomainInvalid);
+			ensure!(
+				!ManifestStates::<T>::contains_key(&domain),
+				Error::<T>::ManifestAlreadyTracked
+			);
+
+			// The manifest is only trusted once proven to be published on the TLD chain, so any
+			// account can ask for it to be ingested. The deposit pays for the work of the offchain
+			// workers checking it until the domain stops being tracked.
+			let deposit = T::ManifestDeposit::get();
+			T::Currency::reserve(&who, deposit).map_err(|_| Error::<T>::InsufficientDeposit)?;
+			ManifestDeposits::<T>::insert(&domain, (who, deposit));
+
+			let checked_at = <frame_system::Pallet<T>>::block_number();
+			ManifestStates::<T>::insert(&domain, ManifestState { content_hash: None, checked_at });
+			Self::deposit_event(Event::ManifestTracked(domain));
+
+			Ok(())
+		}
+
+		#[pallet::call_index(12)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(
+			8 + 6 * payload.data.assets.len() as u64 + 6 * payload.data.existing_assets as u64,
+			4 + 9 * payload.data.assets.len() as u64 + 7 * payload.data.existing_assets as u64,
+		).ref_time() + Pallet::<T>::attestation_weight().ref_time())]
+		pub fn ingest_manifest(
+			origin: OriginFor<T>,
+			payload: OcwPayloadOf<T, ManifestIngestion>,
+			// Checked in `validate_unsigned`
+			_signature: T::Signature,
+		) -> DispatchResult {
+			// Only allow off-chain workers to call this function
+			ensure_none(origin)?;
+			let authority = payload.public.into_account();
+			let ManifestIngestion { domain, proof, assets, asset_proof, existing_assets } =
+				payload.data;
+			let proof = proof.state();
+			let mut state =
+				ManifestStates::<T>::get(&domain).ok_or(Error::<T>::ManifestNotTracked)?;
+			// The weight covers deregistering up to `existing_assets` assets
+			ensure!(
+				AssetCount::<T>::get(&domain) <= existing_assets,
+				Error::<T>::InvalidAssetCount
+			);
+
+			let manifest = match Self::proven_domain_info(&domain, &proof)? {
+				Some(_) => Self::proven_manifest(&domain, &proof)?,
+				None => None,
+			};
+			let subject = AttestationSubject::Manifest(domain.clone());
+			let Some(manifest) = manifest else {
+				if !Self::attest(subject, authority, AttestationOutcome::Unpublished) {
+					return Ok(());
+				}
+				// Unregistered domains and domains without a manifest stop being tracked
+				let (_, removed) = Self::apply_manifest(&domain, Vec::new(), None)?;
+				ManifestStates::<T>::remove(&domain);
+				if let Some((who, deposit)) = ManifestDeposits::<T>::take(&domain) {
+					T::Currency::unreserve(&who, deposit);
+					Self::deposit_event(Event::ManifestDepositReturned(
+						domain.clone(),
+						who,
+						deposit,
+					));
+				}
+				Self::deposit_event(Event::ManifestUntracked(domain, removed));
+				return Ok(());
+			};
+
+			state.checked_at = <frame_system::Pallet<T>>::block_number();
+			if state.content_hash != Some(manifest.content_hash) {
+				ensure!(assets.len() <= MAX_MANIFEST_ENTRIES, Error::<T>::ManifestTooLarge);
+				ensure!(
+					H256(sp_core::hashing::blake2_256(&assets.encode())) == manifest.content_hash,
+					Error::<T>::ManifestMismatch
+				);
+				// The manifest is checked again once more authorities attest it
+				let outcome = AttestationOutcome::Published(manifest.content_hash);
+				if !Self::attest(subject, authority, outcome) {
+					return Ok(());
+				}
+
+				let asset_proof = asset_proof.as_ref().map(ReadProof::state);
+				if let Some(asset_proof) = &asset_proof {
+					Self::check_anchored(&domain, &proof, asset_proof)?;
+				}
+				let (added, removed) = Self::apply_manifest(&domain, assets, asset_proof)?;
+				state.content_hash = Some(manifest.content_hash);
+				Self::deposit_event(Event::ManifestIngested(
+					domain.clone(),
+					manifest.content_hash,
+					added,
+					removed,
+				));
+			}
+			ManifestStates::<T>::insert(&domain, state);
+
+			Ok(())
+		}
+
+		#[pallet::call_index(13)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(5, 6).ref_time())]
+		pub fn withdraw_asset(
+			origin: OriginFor<T>,
+			domain: Vec<u8>,
+			asset_hash: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				ProviderAssets::<T>::contains_key(&domain, &asset_hash),
+				Error::<T>::NotProvider
+			);
+
+			// Ownership is kept on the TLD chain, so the offchain worker checks it
+			Self::request_withdrawal(who, domain, Some(asset_hash))
+		}
+
+		#[pallet::call_index(14)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(5, 6).ref_time())]
+		pub fn withdraw_all(origin: OriginFor<T>, domain: Vec<u8>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(AssetCount::<T>::contains_key(&domain), Error::<T>::NotProvider);
+
+			// Ownership is kept on the TLD chain, so the offchain worker checks it
+			Self::request_withdrawal(who, domain, None)
+		}
+
+		#[pallet::call_index(15)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(
+			10 + 6 * payload.data.withdrawn_assets as u64,
+			6 + 7 * payload.data.withdrawn_assets as u64,
+		).ref_time() + Pallet::<T>::attestation_weight().ref_time())]
+		pub fn complete_withdrawal(
+			origin: OriginFor<T>,
+			payload: OcwPayloadOf<T, WithdrawalProof>,
+			// Checked in `validate_unsigned`
+			_signature: T::Signature,
+		) -> DispatchResult {
+			// Only allow off-chain workers to call this function
+			ensure_none(origin)?;
+			let authority = payload.public.into_account();
+			let WithdrawalProof { request_id, proof, withdrawn_assets } = payload.data;
+			let proof = proof.state();
+			let withdrawal =
+				Withdrawals::<T>::get(request_id).ok_or(Error::<T>::WithdrawalDoesNotExist)?;
+			let current_block_number = <frame_system::Pallet<T>>::block_number();
+			ensure!(
+				current_block_number < withdrawal.expires_at,
+				Error::<T>::WithdrawalDoesNotExist
+			);
+			// The weight covers deregistering up to `withdrawn_assets` assets
+			ensure!(
+				Self::withdrawn_assets(&withdrawal) <= withdrawn_assets,
+				Error::<T>::InvalidAssetCount
+			);
+
+			// Revoked domains are left to `cleanup_revoked_domains`
+			let authorized = match Self::proven_domain_info(&withdrawal.domain, &proof)? {
+				Some(domain_info) => Self::is_authorized(
+					&withdrawal.domain,
+					&withdrawal.requester,
+					&domain_info,
+					&proof,
+				)?,
+				None => false,
+			};
+			let outcome = if authorized {
+				AttestationOutcome::Verified
+			} else {
+				AttestationOutcome::Rejected(RejectionReason::NotAuthorized)
+			};
+			if !Self::attest(AttestationSubject::Request(request_id), authority, outcome) {
+				return Ok(());
+			}
+			Withdrawals::<T>::remove(request_id);
+			RequestExpiries::<T>::remove(withdrawal.expires_at, request_id);
+			Self::settle_deposit(request_id, authorized);
+
+			if !authorized {
+				Self::deposit_event(Event::WithdrawalRejected(request_id));
+				return Ok(());
+			}
+
+			let assets = match withdrawal.asset_hash {
+				Some(asset_hash) => vec![asset_hash],
+				None => ProviderAssets::<T>::iter_key_prefix(&withdrawal.domain).collect(),
+			};
+			for asset in assets {
+				if Self::remove_provider(&asset, &withdrawal.domain) {
+					Self::deposit_event(Event::AssetWithdrawn(asset, withdrawal.domain.clone()));
+				}
+			}
+
+			Ok(())
+		}
+
+		#[pallet::call_index(16)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(
+			2,
+			3 + T::MaxAuthorities::get() as u64,
+		).ref_time())]
+		pub fn untrack_manifest(origin: OriginFor<T>, domain: Vec<u8>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(ManifestStates::<T>::contains_key(&domain), Error::<T>::ManifestNotTracked);
+			let (depositor, deposit) =
+				ManifestDeposits::<T>::get(&domain).ok_or(Error::<T>::NotManifestDepositor)?;
+			ensure!(depositor == who, Error::<T>::NotManifestDepositor);
+
+			// The assets registered from the manifest stay until they are withdrawn or the domain
+			// is revoked, only changes to the manifest are no longer followed
+			ManifestStates::<T>::remove(&domain);
+			ManifestDeposits::<T>::remove(&domain);
+			Self::clear_attestations(&AttestationSubject::Manifest(domain.clone()));
+			T::Currency::unreserve(&who, deposit);
+			Self::deposit_event(Event::ManifestDepositReturned(domain.clone(), who, deposit));
+			Self::deposit_event(Event::ManifestUntracked(domain, 0));
 
 			Ok(())
 		}
@@ -0,0 +1,387 @@
+//! Derives the layout of the domain map of a TLD chain from its runtime metadata.
+//!
+//! TLD chains are upgraded independently of this chain, so the pallet name, the hasher of the map
+//! and the shape of `DomainInfo` cannot be hardcoded. The offchain worker fetches the metadata of
+//! each TLD chain at a finalized header, extracts the layout below and submits it along with a
+//! proof of the runtime code at that header. Once enough authorities attest the same layout for the
+//! same code, the runtime builds storage keys and decodes proven values from it.
+
+use codec::{Decode, DecodeAll, Encode};
+use frame_metadata::{
+	v14::{StorageEntryMetadata, StorageEntryType, StorageHasher},
+	RuntimeMetadata, RuntimeMetadataPrefixed, META_RESERVED,
+};
+use frame_support::RuntimeDebug;
+use scale_info::{
+	form::PortableForm,
+	prelude::{string::String, vec, vec::Vec},
+	PortableRegistry, TypeDef, TypeInfo,
+};
+
+/// Hashers a storage map can use for its keys, mirroring `StorageHasher` of the metadata.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum KeyHasher {
+	Blake2_128,
+	Blake2_256,
+	Blake2_128Concat,
+	Twox128,
+	Twox256,
+	Twox64Concat,
+	Identity,
+}
+
+impl KeyHasher {
+	/// Hashes the SCALE encoded `key` the way the storage map does.
+	pub fn hash(&self, key: &[u8]) -> Vec<u8> {
+		use sp_core::hashing::{blake2_128, blake2_256, twox_128, twox_256, twox_64};
+
+		match self {
+			KeyHasher::Blake2_128 => blake2_128(key).to_vec(),
+			KeyHasher::Blake2_256 => blake2_256(key).to_vec(),
+			KeyHasher::Blake2_128Concat => [&blake2_128(key)[..], key].concat(),
+			KeyHasher::Twox128 => twox_128(key).to_vec(),
+			KeyHasher::Twox256 => twox_256(key).to_vec(),
+			KeyHasher::Twox64Concat => [&twox_64(key)[..], key].concat(),
+			KeyHasher::Identity => key.to_vec(),
+		}
+	}
+}
+
+impl From<&StorageHasher> for KeyHasher {
+	fn from(hasher: &StorageHasher) -> Self {
+		match hasher {
+			StorageHasher::Blake2_128 => KeyHasher::Blake2_128,
+			StorageHasher::Blake2_256 => KeyHasher::Blake2_256,
+			StorageHasher::Blake2_128Concat => KeyHasher::Blake2_128Concat,
+			StorageHasher::Twox128 => KeyHasher::Twox128,
+			StorageHasher::Twox256 => KeyHasher::Twox256,
+			StorageHasher::Twox64Concat => KeyHasher::Twox64Concat,
+			StorageHasher::Identity => KeyHasher::Identity,
+		}
+	}
+}
+
+/// Known shapes of the `DomainInfo` values stored by TLD chains.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum DomainInfoVersion {
+	/// `creator`, `chain_spec`, `maintainer` and `available`
+	V1,
+	/// `V1` followed by `rpc_endpoints`
+	V2,
+}
+
+impl DomainInfoVersion {
+	/// Recognizes a version from the field names of the value type, in order.
+	fn from_fields(fields: &[&str]) -> Option<Self> {
+		match fields {
+			["creator", "chain_spec", "maintainer", "available"] => Some(DomainInfoVersion::V1),
+			["creator", "chain_spec", "maintainer", "available", "rpc_endpoints"] =>
+				Some(DomainInfoVersion::V2),
+			_ => None,
+		}
+	}
+}
+
+/// `DomainInfo` as stored by TLD chains predating RPC endpoints.
+#[derive(Decode)]
+struct DomainInfoV1<AccountId> {
+	creator: AccountId,
+	chain_spec: Vec<u8>,
+	maintainer: Vec<u8>,
+	available: bool,
+}
+
+/// Decodes a `DomainInfo` value of the given version into the current type.
+///
+/// The whole value must be consumed, so that a value of another version is not silently accepted.
+pub fn decode_domain_info<AccountId: Decode>(
+	version: DomainInfoVersion,
+	value: &[u8],
+) -> Result<pallet_tld::DomainInfo<AccountId>, codec::Error> {
+	match version {
+		DomainInfoVersion::V1 => {
+			let info = DomainInfoV1::<AccountId>::decode_all(&mut &value[..])?;
+			Ok(pallet_tld::DomainInfo::new(
+				info.creator,
+				info.chain_spec,
+				info.maintainer,
+				info.available,
+			))
+		},
+		DomainInfoVersion::V2 => pallet_tld::DomainInfo::decode_all(&mut &value[..]),
+	}
+}
+
+/// Where and how a TLD chain stores its domains.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct DomainMapLayout {
+	pub pallet_prefix: Vec<u8>,
+	pub storage_prefix: Vec<u8>,
+	pub hasher: KeyHasher,
+	pub version: DomainInfoVersion,
+}
+
+impl DomainMapLayout {
+	/// Layout of the current `pallet_tld`, stored under `pallet_prefix` and `storage_prefix`.
+	pub fn current(pallet_prefix: Vec<u8>, storage_prefix: Vec<u8>) -> Self {
+		Self {
+			pallet_prefix,
+			storage_prefix,
+			hasher: KeyHasher::Blake2_128Concat,
+			version: DomainInfoVersion::V2,
+		}
+	}
+
+	/// Storage key of `domain` in the domain map.
+	pub fn storage_key(&self, domain: &[u8]) -> Vec<u8> {
+		let mut storage_key = vec![];
+		storage_key.extend(sp_core::hashing::twox_128(&self.pallet_prefix));
+		storage_key.extend(sp_core::hashing::twox_128(&self.storage_prefix));
+		storage_key.extend(self.hasher.hash(&domain.encode()));
+		storage_key
+	}
+}
+
+/// Extracts the layout of the storage map named `storage_name` from SCALE encoded runtime
+/// metadata.
+///
+/// Several pallets may declare a map with that name, in which case the one whose prefix is
+/// `preferred_pallet` wins. Only V14 and V15 metadata are supported.
+pub fn layout_from_metadata(
+	metadata: &[u8],
+	storage_name: &[u8],
+	preferred_pallet: &[u8],
+) -> Result<DomainMapLayout, &'static str> {
+	let prefixed =
+		RuntimeMetadataPrefixed::decode(&mut &metadata[..]).map_err(|_| "Invalid metadata")?;
+	if prefixed.0 != META_RESERVED {
+		return Err("Invalid metadata magic number");
+	}
+
+	// Both versions share the storage metadata types of V14
+	let (types, storages): (&PortableRegistry, Vec<_>) = match &prefixed.1 {
+		RuntimeMetadata::V14(metadata) => (
+			&metadata.types,
+			metadata.pallets.iter().filter_map(|pallet| pallet.storage.as_ref()).collect(),
+		),
+		RuntimeMetadata::V15(metadata) => (
+			&metadata.types,
+			metadata.pallets.iter().filter_map(|pallet| pallet.storage.as_ref()).collect(),
+		),
+		_ => return Err("Unsupported metadata version"),
+	};
+
+	let mut candidates = storages.into_iter().filter_map(|storage| {
+		storage
+			.entries
+			.iter()
+			.find(|entry| entry.name.as_bytes() == storage_name)
+			.map(|entry| (&storage.prefix, entry))
+	});
+	let mut found = candidates.next().ok_or("Storage entry not found in metadata")?;
+	if found.0.as_bytes() != preferred_pallet {
+		if let Some(preferred) =
+			candidates.find(|(prefix, _)| prefix.as_bytes() == preferred_pallet)
+		{
+			found = preferred;
+		}
+	}
+
+	let (pallet_prefix, entry) = found;
+	let (hasher, version) = map_layout(types, entry)?;
+
+	Ok(DomainMapLayout {
+		pallet_prefix: pallet_prefix.as_bytes().to_vec(),
+		storage_prefix: storage_name.to_vec(),
+		hasher,
+		version,
+	})
+}
+
+fn map_layout(
+	types: &PortableRegistry,
+	entry: &StorageEntryMetadata<PortableForm>,
+) -> Result<(KeyHasher, DomainInfoVersion), &'static str> {
+	let (hasher, value) = match &entry.ty {
+		StorageEntryType::Map { hashers, value, .. } if hashers.len() == 1 =>
+			(KeyHasher::from(&hashers[0]), value),
+		_ => return Err("Storage entry is not a single key map"),
+	};
+
+	let value_type = types.resolve(value.id).ok_or("Value type not found in metadata")?;
+	let TypeDef::Composite(composite) = &value_type.type_def else {
+		return Err("Value type is not a struct");
+	};
+	let fields: Vec<&str> = composite
+		.fields
+		.iter()
+		.map(|field| field.name.as_ref().map(String::as_str).unwrap_or_default())
+		.collect();
+	let version = DomainInfoVersion::from_fields(&fields).ok_or("Unknown DomainInfo version")?;
+
+	Ok((hasher, version))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_metadata::v14::{
+		ExtrinsicMetadata, PalletMetadata, PalletStorageMetadata, RuntimeMetadataV14,
+		StorageEntryModifier,
+	};
+	use scale_info::{meta_type, MetaType};
+
+	/// `DomainInfo` of TLD chains predating RPC endpoints, as described by their metadata.
+	#[derive(Encode, TypeInfo)]
+	struct LegacyDomainInfo {
+		creator: u64,
+		chain_spec: Vec<u8>,
+		maintainer: Vec<u8>,
+		available: bool,
+	}
+
+	fn entry(name: &'static str, ty: StorageEntryType) -> StorageEntryMetadata {
+		StorageEntryMetadata {
+			name,
+			modifier: StorageEntryModifier::Optional,
+			ty,
+			default: vec![0],
+			docs: vec![],
+		}
+	}
+
+	fn domain_map(hasher: StorageHasher, value: MetaType) -> StorageEntryMetadata {
+		entry(
+			"DomainMap",
+			StorageEntryType::Map { hashers: vec![hasher], key: meta_type::<Vec<u8>>(), value },
+		)
+	}
+
+	fn pallet(name: &'static str, index: u8, entries: Vec<StorageEntryMetadata>) -> PalletMetadata {
+		PalletMetadata {
+			name,
+			storage: Some(PalletStorageMetadata { prefix: name, entries }),
+			calls: None,
+			event: None,
+			constants: vec![],
+			error: None,
+			index,
+		}
+	}
+
+	fn metadata(pallets: Vec<PalletMetadata>) -> Vec<u8> {
+		let extrinsic =
+			ExtrinsicMetadata { ty: meta_type::<()>(), version: 4, signed_extensions: vec![] };
+		let metadata = RuntimeMetadataV14::new(pallets, extrinsic, meta_type::<()>());
+		RuntimeMetadataPrefixed::from(metadata).encode()
+	}
+
+	#[test]
+	fn extracts_current_layout() {
+		let metadata = metadata(vec![pallet(
+			"TldModule",
+			8,
+			vec![domain_map(
+				StorageHasher::Blake2_128Concat,
+				meta_type::<pallet_tld::DomainInfo<u64>>(),
+			)],
+		)]);
+
+		assert_eq!(
+			layout_from_metadata(&metadata, b"DomainMap", b"TldModule"),
+			Ok(DomainMapLayout::current(b"TldModule".to_vec(), b"DomainMap".to_vec()))
+		);
+	}
+
+	#[test]
+	fn extracts_legacy_layout_and_hasher() {
+		let metadata = metadata(vec![pallet(
+			"Domains",
+			3,
+			vec![domain_map(StorageHasher::Twox64Concat, meta_type::<LegacyDomainInfo>())],
+		)]);
+
+		let layout = layout_from_metadata(&metadata, b"DomainMap", b"TldModule").unwrap();
+		assert_eq!(layout.pallet_prefix, b"Domains".to_vec());
+		assert_eq!(layout.hasher, KeyHasher::Twox64Concat);
+		assert_eq!(layout.version, DomainInfoVersion::V1);
+	}
+
+	#[test]
+	fn prefers_the_given_pallet() {
+		let legacy = domain_map(StorageHasher::Twox64Concat, meta_type::<LegacyDomainInfo>());
+		let current =
+			domain_map(StorageHasher::Blake2_128Concat, meta_type::<pallet_tld::DomainInfo<u64>>());
+		let metadata = metadata(vec![
+			pallet("Domains", 3, vec![legacy]),
+			pallet("TldModule", 8, vec![current]),
+		]);
+
+		let layout = layout_from_metadata(&metadata, b"DomainMap", b"TldModule").unwrap();
+		assert_eq!(layout.pallet_prefix, b"TldModule".to_vec());
+		assert_eq!(layout.version, DomainInfoVersion::V2);
+	}
+
+	#[test]
+	fn rejects_unusable_entries() {
+		let plain = metadata(vec![pallet(
+			"TldModule",
+			8,
+			vec![entry("DomainMap", StorageEntryType::Plain(meta_type::<LegacyDomainInfo>()))],
+		)]);
+		assert_eq!(
+			layout_from_metadata(&plain, b"DomainMap", b"TldModule"),
+			Err("Storage entry is not a single key map")
+		);
+
+		let unknown_value = metadata(vec![pallet(
+			"TldModule",
+			8,
+			vec![domain_map(StorageHasher::Blake2_128Concat, meta_type::<(u64, bool)>())],
+		)]);
+		assert_eq!(
+			layout_from_metadata(&unknown_value, b"DomainMap", b"TldModule"),
+			Err("Value type is not a struct")
+		);
+
+		assert_eq!(
+			layout_from_metadata(&plain, b"Domains", b"TldModule"),
+			Err("Storage entry not found in metadata")
+		);
+		assert_eq!(
+			layout_from_metadata(&[1, 2, 3], b"DomainMap", b"TldModule"),
+			Err("Invalid metadata")
+		);
+	}
+
+	#[test]
+	fn builds_storage_keys() {
+		let layout = DomainMapLayout::current(b"TldModule".to_vec(), b"DomainMap".to_vec());
+		let domain = b"example.tld";
+
+		let key = layout.storage_key(domain);
+		let encoded = domain.to_vec().encode();
+		assert_eq!(&key[..16], &sp_core::hashing::twox_128(b"TldModule")[..]);
+		assert_eq!(&key[16..32], &sp_core::hashing::twox_128(b"DomainMap")[..]);
+		assert_eq!(&key[32..48], &sp_core::hashing::blake2_128(&encoded)[..]);
+		assert_eq!(&key[48..], &encoded[..]);
+	}
+
+	#[test]
+	fn decodes_domain_info_of_its_version_only() {
+		let legacy = LegacyDomainInfo {
+			creator: 1,
+			chain_spec: b"spec".to_vec(),
+			maintainer: b"node".to_vec(),
+			available: true,
+		}
+		.encode();
+		let info = decode_domain_info::<u64>(DomainInfoVersion::V1, &legacy).unwrap();
+		assert_eq!(info, pallet_tld::DomainInfo::new(1, b"spec".to_vec(), b"node".to_vec(), true));
+		assert!(decode_domain_info::<u64>(DomainInfoVersion::V2, &legacy).is_err());
+
+		let current = info.encode();
+		assert_eq!(decode_domain_info::<u64>(DomainInfoVersion::V2, &current).unwrap(), info);
+		assert!(decode_domain_info::<u64>(DomainInfoVersion::V1, &current).is_err());
+	}
+}
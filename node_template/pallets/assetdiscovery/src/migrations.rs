@@ -0,0 +1,100 @@
+//! Storage migrations of the asset discovery pallet.
+
+use super::*;
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+use scale_info::prelude::vec::Vec;
+
+/// Moves the provider lists of the initial layout into the deduplicated double maps and their
+/// counters, and schedules the providers for revalidation.
+pub mod v1 {
+	use super::*;
+	use frame_support::{migrations::VersionedMigration, storage_alias};
+
+	/// Storage items of the initial layout.
+	mod old {
+		use super::*;
+
+		// Domains providing each asset, in a `ProviderList`
+		#[storage_alias]
+		pub type AssetProviders<T: Config> =
+			StorageMap<Pallet<T>, Blake2_128Concat, Vec<u8>, Vec<Vec<u8>>, OptionQuery>;
+
+		// Assets provided by each domain, in an `AssetList`
+		#[storage_alias]
+		pub type ProviderAssets<T: Config> =
+			StorageMap<Pallet<T>, Blake2_128Concat, Vec<u8>, Vec<Vec<u8>>, OptionQuery>;
+
+		// Requests by asset hash, whose name is now used by the pending request counters
+		#[storage_alias]
+		pub type PendingRequests<T: Config> =
+			StorageMap<Pallet<T>, Blake2_128Concat, Vec<u8>, (), OptionQuery>;
+
+		#[storage_alias]
+		pub type LastProcessedDomain<T: Config> = StorageValue<Pallet<T>, Vec<u8>, OptionQuery>;
+	}
+
+	pub struct UncheckedMigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for UncheckedMigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			// Both layouts share the same prefixes, so the old entries are taken out first
+			let lists: Vec<(Vec<u8>, Vec<Vec<u8>>)> = old::AssetProviders::<T>::drain().collect();
+			let cleared = old::ProviderAssets::<T>::clear(u32::MAX, None).unique as u64;
+			// Pending requests of the initial layout were never verified and cannot be
+			// expressed as requests with an id, so they are dropped
+			let dropped = old::PendingRequests::<T>::clear(u32::MAX, None).unique as u64;
+			old::LastProcessedDomain::<T>::kill();
+
+			// Legacy providers were registered before requests had ids, so they are recorded
+			// like manifest entries
+			let now = <frame_system::Pallet<T>>::block_number();
+			let info = ProviderInfo { request_id: None, verified_at: now };
+			let check = ProviderCheck { last_verified: now, last_attempted: now, failures: 0 };
+			let mut duplicates = 0u64;
+			let mut migrated = 0u64;
+			let mut scheduled = 0u64;
+			for (asset, domains) in lists.iter() {
+				for domain in domains {
+					// The initial lists were not deduplicated
+					if AssetProviders::<T>::contains_key(asset, domain) {
+						duplicates += 1;
+						continue;
+					}
+					AssetProviders::<T>::insert(asset, domain, &info);
+					ProviderAssets::<T>::insert(domain, asset, &info);
+					ProviderCount::<T>::mutate(asset, |count| *count = count.saturating_add(1));
+					AssetCount::<T>::mutate(domain, |count| *count = count.saturating_add(1));
+					// Revalidation of the legacy providers starts a full interval from now
+					if !ProviderChecks::<T>::contains_key(domain) {
+						ProviderChecks::<T>::insert(domain, &check);
+						scheduled += 1;
+					}
+					migrated += 1;
+				}
+			}
+			log::info!(
+				"Migrated {} providers to v1, scheduled {} domains, dropped {} duplicates and {} \
+				 pending requests",
+				migrated,
+				scheduled,
+				duplicates,
+				dropped
+			);
+
+			let lists = lists.len() as u64;
+			T::DbWeight::get().reads_writes(
+				lists + cleared + dropped + duplicates + 4 * migrated,
+				lists + cleared + dropped + 1 + 4 * migrated + scheduled,
+			)
+		}
+	}
+
+	/// Migrates the providers once, when the storage version of the pallet is 0.
+	pub type MigrateToV1<T> = VersionedMigration<
+		0,
+		1,
+		UncheckedMigrateToV1<T>,
+		Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+}
@@ -0,0 +1,253 @@
+//! A minimal `no_std` parser for the textual multiaddresses found in chain specifications.
+//!
+//! Only the protocols used by Substrate boot nodes are understood, which is enough to recover the
+//! host of a node and guess where its RPC server listens.
+
+use core::{
+	net::{Ipv4Addr, Ipv6Addr},
+	str::FromStr,
+};
+use scale_info::prelude::{format, string::String, vec::Vec};
+
+/// Port the BCDNS nodes serve JSON-RPC on, see `scripts/entrypoint.sh`.
+pub const DEFAULT_RPC_PORT: u16 = 9945;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Host {
+	Ip4(Ipv4Addr),
+	Ip6(Ipv6Addr),
+	// Covers `/dns`, `/dns4` and `/dns6`, which only differ in the records they resolve
+	Dns(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transport {
+	Tcp,
+	Ws,
+	Wss,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Multiaddr {
+	pub host: Host,
+	pub port: u16,
+	pub transport: Transport,
+	pub peer_id: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MultiaddrError {
+	/// The address does not start with '/'
+	NotAMultiaddr,
+	/// A protocol name this parser does not know about
+	UnsupportedProtocol,
+	/// A protocol is missing its value or its value is malformed
+	InvalidValue,
+	/// The address has no host or no TCP port
+	Incomplete,
+}
+
+impl FromStr for Multiaddr {
+	type Err = MultiaddrError;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		let rest = value.trim().strip_prefix('/').ok_or(MultiaddrError::NotAMultiaddr)?;
+		let mut parts = rest.split('/');
+
+		let mut host = None;
+		let mut port = None;
+		let mut transport = Transport::Tcp;
+		let mut peer_id = None;
+
+		while let Some(protocol) = parts.next() {
+			match protocol {
+				"ip4" => {
+					let ip = parts.next().and_then(|ip| ip.parse().ok());
+					host = Some(Host::Ip4(ip.ok_or(MultiaddrError::InvalidValue)?));
+				},
+				"ip6" => {
+					let ip = parts.next().and_then(|ip| ip.parse().ok());
+					host = Some(Host::Ip6(ip.ok_or(MultiaddrError::InvalidValue)?));
+				},
+				"dns" | "dns4" | "dns6" => {
+					let name = parts.next().filter(|name| is_dns_name(name));
+					host = Some(Host::Dns(name.ok_or(MultiaddrError::InvalidValue)?.into()));
+				},
+				"tcp" => {
+					let tcp_port = parts.next().and_then(|port| port.parse().ok());
+					port = Some(tcp_port.ok_or(MultiaddrError::InvalidValue)?);
+				},
+				// `/tls/ws` is the newer spelling of `/wss`
+				"ws" if transport == Transport::Wss => {},
+				"ws" => transport = Transport::Ws,
+				"wss" | "tls" => transport = Transport::Wss,
+				// The server name sent over TLS, which does not change where the node is reached
+				"sni" => {
+					parts
+						.next()
+						.filter(|name| is_dns_name(name))
+						.ok_or(MultiaddrError::InvalidValue)?;
+				},
+				"p2p" | "ipfs" => {
+					let id = parts.next().filter(|id| !id.is_empty());
+					peer_id = Some(id.ok_or(MultiaddrError::InvalidValue)?.into());
+				},
+				_ => return Err(MultiaddrError::UnsupportedProtocol),
+			}
+		}
+
+		match (host, port) {
+			(Some(host), Some(port)) => Ok(Multiaddr { host, port, transport, peer_id }),
+			_ => Err(MultiaddrError::Incomplete),
+		}
+	}
+}
+
+impl Multiaddr {
+	/// HTTP endpoint of the RPC server of the node behind this address, assuming it listens on
+	/// `rpc_port` of the same host.
+	///
+	/// The port of a multiaddress is the p2p port, which is never the RPC one, so the caller has to
+	/// provide it.
+	pub fn rpc_endpoint(&self, rpc_port: u16) -> String {
+		let scheme = match self.transport {
+			Transport::Wss => "https",
+			Transport::Tcp | Transport::Ws => "http",
+		};
+		match &self.host {
+			Host::Ip4(ip) => format!("{}://{}:{}", scheme, ip, rpc_port),
+			Host::Ip6(ip) => format!("{}://[{}]:{}", scheme, ip, rpc_port),
+			Host::Dns(name) => format!("{}://{}:{}", scheme, name, rpc_port),
+		}
+	}
+}
+
+fn is_dns_name(name: &str) -> bool {
+	!name.is_empty() &&
+		name.len() <= 253 &&
+		name.split('.').all(|label| {
+			!label.is_empty() &&
+				label.len() <= 63 &&
+				!label.starts_with('-') &&
+				!label.ends_with('-') &&
+				label.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+		})
+}
+
+/// Checks that an explicitly registered RPC endpoint is an HTTP(S) URL and returns it as a string.
+pub fn parse_rpc_endpoint(endpoint: &[u8]) -> Option<&str> {
+	let endpoint = core::str::from_utf8(endpoint).ok()?;
+	let authority =
+		endpoint.strip_prefix("http://").or_else(|| endpoint.strip_prefix("https://"))?;
+	(!authority.is_empty() && !authority.contains(char::is_whitespace)).then_some(endpoint)
+}
+
+/// RPC endpoints derived from the boot nodes of a chain spec, skipping unparsable entries.
+pub fn boot_node_endpoints(boot_nodes: &[String]) -> Vec<String> {
+	boot_nodes
+		.iter()
+		.filter_map(|boot_node| match boot_node.parse::<Multiaddr>() {
+			Ok(multiaddr) => Some(multiaddr.rpc_endpoint(DEFAULT_RPC_PORT)),
+			Err(err) => {
+				log::info!("Skipping boot node {}: {:?}", boot_node, err);
+				None
+			},
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const PEER_ID: &str = "12D3KooWEyoppNCUx8Yx66oV9fJnriXwCcXwDDUA2kj6vnc6iDEp";
+
+	fn parse(value: &str) -> Multiaddr {
+		value.parse().expect("valid multiaddress")
+	}
+
+	#[test]
+	fn parses_tcp_address() {
+		let multiaddr = parse("/ip4/10.0.0.1/tcp/30333");
+		assert_eq!(multiaddr.host, Host::Ip4(Ipv4Addr::new(10, 0, 0, 1)));
+		assert_eq!(multiaddr.port, 30333);
+		assert_eq!(multiaddr.transport, Transport::Tcp);
+		assert_eq!(multiaddr.peer_id, None);
+		assert_eq!(multiaddr.rpc_endpoint(DEFAULT_RPC_PORT), "http://10.0.0.1:9945");
+	}
+
+	#[test]
+	fn parses_websocket_transports() {
+		assert_eq!(parse("/dns/node.example.com/tcp/30333/ws").transport, Transport::Ws);
+		assert_eq!(parse("/dns/node.example.com/tcp/443/wss").transport, Transport::Wss);
+		assert_eq!(parse("/dns/node.example.com/tcp/443/tls/ws").transport, Transport::Wss);
+		assert_eq!(
+			parse("/dns4/node.example.com/tcp/443/tls/sni/node.example.com/ws").transport,
+			Transport::Wss
+		);
+		assert_eq!(
+			parse("/dns/node.example.com/tcp/443/tls/ws").rpc_endpoint(DEFAULT_RPC_PORT),
+			"https://node.example.com:9945"
+		);
+	}
+
+	#[test]
+	fn parses_ip6_address() {
+		let multiaddr = parse("/ip6/::1/tcp/30333/ws");
+		assert_eq!(multiaddr.host, Host::Ip6(Ipv6Addr::LOCALHOST));
+		assert_eq!(multiaddr.transport, Transport::Ws);
+		assert_eq!(multiaddr.rpc_endpoint(9944), "http://[::1]:9944");
+	}
+
+	#[test]
+	fn parses_peer_id_suffix() {
+		let multiaddr = parse(&format!("/dns/node.example.com/tcp/443/tls/ws/p2p/{}", PEER_ID));
+		assert_eq!(multiaddr.transport, Transport::Wss);
+		assert_eq!(multiaddr.peer_id.as_deref(), Some(PEER_ID));
+		assert_eq!(
+			parse(&format!("/ip4/10.0.0.1/tcp/30333/ipfs/{}", PEER_ID)).peer_id.as_deref(),
+			Some(PEER_ID)
+		);
+	}
+
+	#[test]
+	fn rejects_malformed_addresses() {
+		assert_eq!(
+			"ip4/10.0.0.1/tcp/30333".parse::<Multiaddr>(),
+			Err(MultiaddrError::NotAMultiaddr)
+		);
+		assert_eq!(
+			"/ip4/10.0.0.1/udp/30333".parse::<Multiaddr>(),
+			Err(MultiaddrError::UnsupportedProtocol)
+		);
+		assert_eq!(
+			"/ip4/10.0.0.300/tcp/30333".parse::<Multiaddr>(),
+			Err(MultiaddrError::InvalidValue)
+		);
+		assert_eq!(
+			"/dns/-node.example.com/tcp/30333".parse::<Multiaddr>(),
+			Err(MultiaddrError::InvalidValue)
+		);
+		assert_eq!(
+			"/dns/node.example.com/tcp/443/tls/sni//ws".parse::<Multiaddr>(),
+			Err(MultiaddrError::InvalidValue)
+		);
+		assert_eq!(
+			format!("/ip4/10.0.0.1/p2p/{}", PEER_ID).parse::<Multiaddr>(),
+			Err(MultiaddrError::Incomplete)
+		);
+	}
+
+	#[test]
+	fn derives_boot_node_endpoints() {
+		let boot_nodes = [
+			format!("/dns/node.example.com/tcp/443/wss/p2p/{}", PEER_ID),
+			"not a multiaddress".into(),
+			format!("/ip4/10.0.0.1/tcp/30333/p2p/{}", PEER_ID),
+		];
+		assert_eq!(
+			boot_node_endpoints(&boot_nodes),
+			["https://node.example.com:9945", "http://10.0.0.1:9945"]
+		);
+	}
+}
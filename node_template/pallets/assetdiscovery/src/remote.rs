@@ -0,0 +1,289 @@
+//! Helpers used by the offchain worker to query remote chains over JSON-RPC and to check the
+//! storage proofs they return.
+
+use codec::{Decode, Encode};
+use frame_support::{Deserialize, RuntimeDebug};
+use scale_info::{
+	prelude::{format, string::String, vec, vec::Vec},
+	TypeInfo,
+};
+use serde::de::DeserializeOwned;
+use sp_core::{
+	offchain::{Duration, Timestamp},
+	Blake2Hasher, H256,
+};
+use sp_runtime::{generic, offchain::http, traits::Header as HeaderT, DigestItem};
+use sp_trie::{read_trie_value, trie_types::TrieDBBuilder, LayoutV1, MemoryDB, StorageProof, Trie};
+
+/// Header type of the remote TLD and target chains, which run the same node template.
+pub type RemoteHeader = pallet_lightclient::TldHeader;
+
+/// A storage read proof taken at a given block of a remote chain.
+///
+/// The runtime checks the proof against the state root of `header` before trusting any value read
+/// from it.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ReadProof {
+	pub header: RemoteHeader,
+	// Trie nodes returned by `state_getReadProof`
+	pub nodes: Vec<Vec<u8>>,
+}
+
+impl ReadProof {
+	/// Hashes the trie nodes of the proof into a database, from which any number of values can
+	/// then be read.
+	pub fn state(&self) -> ProvenState {
+		ProvenState {
+			header: self.header.clone(),
+			db: StorageProof::new(self.nodes.iter().cloned()).into_memory_db(),
+		}
+	}
+
+	/// Drops the node holding the value stored under `key`, keeping the proof of its hash.
+	///
+	/// Values larger than a hash are stored apart from their trie node, so values too large to be
+	/// submitted, such as the runtime code, can still be identified by their hash.
+	pub fn without_value(mut self, key: &[u8]) -> Result<Self, &'static str> {
+		let hash = self.state().read_value_hash(key)?.ok_or("Key not set in the proof")?;
+		self.nodes.retain(|node| H256(sp_core::hashing::blake2_256(node)) != hash);
+		Ok(self)
+	}
+}
+
+/// The state of a remote chain at the header a [`ReadProof`] was taken at.
+pub struct ProvenState {
+	pub header: RemoteHeader,
+	db: MemoryDB<Blake2Hasher>,
+}
+
+impl ProvenState {
+	/// Reads the value stored under `key`.
+	///
+	/// Returns `Ok(None)` if the proof shows the key is not set and `Err` if the proof does not
+	/// cover the key or is not rooted at the header's state root.
+	pub fn read_value(&self, key: &[u8]) -> Result<Option<Vec<u8>>, &'static str> {
+		read_trie_value::<LayoutV1<Blake2Hasher>, _>(
+			&self.db,
+			self.header.state_root(),
+			key,
+			None,
+			None,
+		)
+		.map_err(|_| "Invalid storage proof")
+	}
+
+	/// Reads the hash of the value stored under `key`, which does not need the value itself to be
+	/// part of the proof.
+	pub fn read_value_hash(&self, key: &[u8]) -> Result<Option<H256>, &'static str> {
+		TrieDBBuilder::<Blake2Hasher>::new(&self.db, self.header.state_root())
+			.build()
+			.get_hash(key)
+			.map_err(|_| "Invalid storage proof")
+	}
+}
+
+#[derive(Deserialize, Debug)]
+struct RPCResponse<R> {
+	result: Option<R>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RPCHeader {
+	parent_hash: String,
+	number: String,
+	state_root: String,
+	extrinsics_root: String,
+	digest: RPCDigest,
+}
+
+#[derive(Deserialize, Debug)]
+struct RPCDigest {
+	logs: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RPCReadProof {
+	proof: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RPCRuntimeVersion {
+	spec_version: u32,
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, &'static str> {
+	hex::decode(value.trim_start_matches("0x")).map_err(|_| "Invalid hex in RPC response")
+}
+
+fn decode_hash(value: &str) -> Result<H256, &'static str> {
+	let bytes = decode_hex(value)?;
+	if bytes.len() != 32 {
+		return Err("Invalid hash in RPC response");
+	}
+	Ok(H256::from_slice(&bytes))
+}
+
+/// The point in time at which requests started now with a timeout of `timeout_ms` give up.
+pub fn deadline_in(timeout_ms: u64) -> Timestamp {
+	sp_io::offchain::timestamp().add(Duration::from_millis(timeout_ms))
+}
+
+/// Sends a JSON-RPC request to `endpoint` and returns its decoded `result`.
+///
+/// `params` must already be a JSON array.
+pub fn rpc_request<R: DeserializeOwned>(
+	endpoint: &str,
+	method: &str,
+	params: &str,
+	deadline: Timestamp,
+) -> Result<Option<R>, &'static str> {
+	let body = format!(r#"{{"id":1,"jsonrpc":"2.0","method":"{}","params":{}}}"#, method, params);
+
+	let pending = http::Request::post(endpoint, vec![body])
+		.add_header("content-type", "application/json")
+		.deadline(deadline)
+		.send()
+		.map_err(|_| "Failed to send RPC request")?;
+
+	let response = pending
+		.try_wait(deadline)
+		.map_err(|_| "RPC request timed out")?
+		.map_err(|_| "Failed to get RPC response")?;
+
+	if response.code != 200 {
+		log::info!("RPC request failed with code: {}", response.code);
+		return Err("Unexpected response code");
+	}
+
+	let body = response.body().collect::<Vec<u8>>();
+	let decoded = serde_json::from_slice::<RPCResponse<R>>(&body)
+		.map_err(|_| "Failed to parse RPC response")?;
+
+	Ok(decoded.result)
+}
+
+/// Downloads the file at `url`, giving up on files larger than `max_len` bytes.
+pub fn fetch_file(url: &str, max_len: usize, deadline: Timestamp) -> Result<Vec<u8>, &'static str> {
+	let pending = http::Request::get(url)
+		.deadline(deadline)
+		.send()
+		.map_err(|_| "Failed to send HTTP request")?;
+
+	let response = pending
+		.try_wait(deadline)
+		.map_err(|_| "HTTP request timed out")?
+		.map_err(|_| "Failed to get HTTP response")?;
+
+	if response.code != 200 {
+		log::info!("HTTP request failed with code: {}", response.code);
+		return Err("Unexpected response code");
+	}
+
+	let mut body = Vec::new();
+	for byte in response.body() {
+		if body.len() >= max_len {
+			return Err("File too large");
+		}
+		body.push(byte);
+	}
+
+	Ok(body)
+}
+
+/// Fetches the hash of the latest finalized block of the chain behind `endpoint`.
+pub fn finalized_head(endpoint: &str, deadline: Timestamp) -> Result<H256, &'static str> {
+	let hash = rpc_request::<String>(endpoint, "chain_getFinalizedHead", "[]", deadline)?
+		.ok_or("Missing finalized head")?;
+	decode_hash(&hash)
+}
+
+/// Fetches the header of block `hash` and checks that it actually hashes to `hash`.
+pub fn header(
+	endpoint: &str,
+	hash: H256,
+	deadline: Timestamp,
+) -> Result<RemoteHeader, &'static str> {
+	let params = format!(r#"["0x{}"]"#, hex::encode(hash));
+	let header = rpc_request::<RPCHeader>(endpoint, "chain_getHeader", &params, deadline)?
+		.ok_or("Missing header")?;
+
+	let number = u32::from_str_radix(header.number.trim_start_matches("0x"), 16)
+		.map_err(|_| "Invalid block number in RPC response")?;
+	let mut digest = generic::Digest::default();
+	for log in header.digest.logs {
+		let item = DigestItem::decode(&mut &decode_hex(&log)?[..])
+			.map_err(|_| "Invalid digest item in RPC response")?;
+		digest.push(item);
+	}
+
+	let header = RemoteHeader::new(
+		number,
+		decode_hash(&header.extrinsics_root)?,
+		decode_hash(&header.state_root)?,
+		decode_hash(&header.parent_hash)?,
+		digest,
+	);
+
+	if header.hash() != hash {
+		return Err("Header does not match the requested hash");
+	}
+
+	Ok(header)
+}
+
+/// Fetches a proof of the storage values under `keys` at block `hash`.
+pub fn read_proof(
+	endpoint: &str,
+	keys: &[Vec<u8>],
+	hash: H256,
+	deadline: Timestamp,
+) -> Result<ReadProof, &'static str> {
+	let header = header(endpoint, hash, deadline)?;
+
+	let keys = keys
+		.iter()
+		.map(|key| format!(r#""0x{}""#, hex::encode(key)))
+		.collect::<Vec<_>>();
+	let params = format!(r#"[[{}], "0x{}"]"#, keys.join(","), hex::encode(hash));
+	let proof = rpc_request::<RPCReadProof>(endpoint, "state_getReadProof", &params, deadline)?
+		.ok_or("Missing read proof")?;
+
+	let nodes = proof.proof.iter().map(|node| decode_hex(node)).collect::<Result<Vec<_>, _>>()?;
+
+	Ok(ReadProof { header, nodes })
+}
+
+/// Fetches the spec version of the runtime of the chain behind `endpoint` at block `hash`.
+pub fn spec_version(endpoint: &str, hash: H256, deadline: Timestamp) -> Result<u32, &'static str> {
+	let params = format!(r#"["0x{}"]"#, hex::encode(hash));
+	let version =
+		rpc_request::<RPCRuntimeVersion>(endpoint, "state_getRuntimeVersion", &params, deadline)?
+			.ok_or("Missing runtime version")?;
+	Ok(version.spec_version)
+}
+
+/// Fetches the SCALE encoded runtime metadata of the chain behind `endpoint` at block `hash`,
+/// preferring V15 and falling back on the default version served by `state_getMetadata`.
+pub fn metadata(endpoint: &str, hash: H256, deadline: Timestamp) -> Result<Vec<u8>, &'static str> {
+	// `Metadata_metadata_at_version(15)` returns an `Option<OpaqueMetadata>`
+	let params = format!(
+		r#"["Metadata_metadata_at_version", "0x{}", "0x{}"]"#,
+		hex::encode(15u32.encode()),
+		hex::encode(hash)
+	);
+	let at_version = rpc_request::<String>(endpoint, "state_call", &params, deadline)
+		.ok()
+		.flatten()
+		.and_then(|result| decode_hex(&result).ok())
+		.and_then(|result| Option::<Vec<u8>>::decode(&mut &result[..]).ok().flatten());
+	if let Some(metadata) = at_version {
+		return Ok(metadata);
+	}
+
+	let params = format!(r#"["0x{}"]"#, hex::encode(hash));
+	let metadata = rpc_request::<String>(endpoint, "state_getMetadata", &params, deadline)?
+		.ok_or("Missing metadata")?;
+	decode_hex(&metadata)
+}
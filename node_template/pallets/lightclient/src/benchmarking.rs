@@ -0,0 +1,139 @@
+//! Benchmarking setup for pallet-lightclient
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as LightClient;
+use frame_benchmarking::v2::*;
+use frame_system::RawOrigin;
+use pallet_rootdns::FinalityCheckpoint;
+use scale_info::prelude::{vec, vec::Vec};
+use sp_consensus_grandpa::{AuthorityId, AuthorityList};
+use sp_core::H256;
+use sp_runtime::{traits::Header as HeaderT, RuntimeAppPublic};
+
+const TLD_NAME: &[u8] = b"bench";
+
+fn header(number: u32, parent_hash: H256) -> TldHeader {
+	TldHeader::new(number, H256::zero(), H256::zero(), parent_hash, Default::default())
+}
+
+/// Registers the benchmark TLD with a checkpoint at `checkpoint`, finalized by `authorities`.
+fn register_tld<T: Config>(checkpoint: &TldHeader, authorities: AuthorityList) {
+	let owner: <T::PalletRootDNS as frame_system::Config>::AccountId = whitelisted_caller();
+	pallet_rootdns::Pallet::<T::PalletRootDNS>::register_tld(
+		RawOrigin::Signed(owner.clone()).into(),
+		TLD_NAME.to_vec(),
+		Vec::new(),
+	)
+	.unwrap();
+	pallet_rootdns::Pallet::<T::PalletRootDNS>::set_finality_checkpoint(
+		RawOrigin::Signed(owner).into(),
+		TLD_NAME.to_vec(),
+		FinalityCheckpoint {
+			header_hash: checkpoint.hash(),
+			number: *checkpoint.number(),
+			state_root: *checkpoint.state_root(),
+			authorities,
+			set_id: 0,
+		},
+	)
+	.unwrap();
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn initialize() {
+		register_tld::<T>(&header(0, H256::zero()), vec![(AuthorityId::generate_pair(None), 1)]);
+		let caller: T::AccountId = whitelisted_caller();
+		#[extrinsic_call]
+		initialize(RawOrigin::Signed(caller), TLD_NAME.to_vec());
+
+		assert!(LightClient::<T>::is_tracked(TLD_NAME));
+	}
+
+	#[benchmark]
+	fn submit_finality_proof(
+		p: Linear<1, 256>,
+		a: Linear<0, 256>,
+		h: Linear<0, 256>,
+	) -> Result<(), BenchmarkError> {
+		let checkpoint = header(0, H256::zero());
+		let authorities: Vec<AuthorityId> =
+			(0..p).map(|_| AuthorityId::generate_pair(None)).collect();
+		register_tld::<T>(&checkpoint, authorities.iter().map(|id| (id.clone(), 1)).collect());
+		LightClient::<T>::initialize(
+			RawOrigin::Signed(whitelisted_caller()).into(),
+			TLD_NAME.to_vec(),
+		)?;
+
+		// `h` headers between the checkpoint and the imported header.
+		let mut ancestry = Vec::new();
+		let mut parent = checkpoint;
+		for number in 1..=h {
+			let next = header(number, parent.hash());
+			ancestry.push(next.clone());
+			parent = next;
+		}
+		let target = header(h + 1, parent.hash());
+
+		// `a` descendants of the imported header, all precommits voting for the last of them.
+		let mut votes_ancestries = Vec::new();
+		let mut vote = target.clone();
+		for number in h + 2..h + 2 + a {
+			vote = header(number, vote.hash());
+			votes_ancestries.push(vote.clone());
+		}
+		let precommit =
+			finality_grandpa::Precommit { target_hash: vote.hash(), target_number: *vote.number() };
+		let message = finality_grandpa::Message::Precommit(precommit.clone());
+		let payload = sp_consensus_grandpa::localized_payload(1, 0, &message);
+		let precommits = authorities
+			.into_iter()
+			.map(|id| finality_grandpa::SignedPrecommit {
+				precommit: precommit.clone(),
+				signature: id.sign(&payload).expect("keys were generated in the keystore"),
+				id,
+			})
+			.collect();
+		let justification = GrandpaJustification {
+			round: 1,
+			commit: finality_grandpa::Commit {
+				target_hash: target.hash(),
+				target_number: h + 1,
+				precommits,
+			},
+			votes_ancestries,
+		};
+
+		let caller: T::AccountId = whitelisted_caller();
+		#[extrinsic_call]
+		submit_finality_proof(
+			RawOrigin::Signed(caller),
+			TLD_NAME.to_vec(),
+			target.clone(),
+			justification,
+			ancestry,
+		);
+
+		assert_eq!(LightClient::<T>::best_finalized(TLD_NAME), Some((h + 1, target.hash())));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn reset() -> Result<(), BenchmarkError> {
+		register_tld::<T>(&header(0, H256::zero()), vec![(AuthorityId::generate_pair(None), 1)]);
+		LightClient::<T>::initialize(
+			RawOrigin::Signed(whitelisted_caller()).into(),
+			TLD_NAME.to_vec(),
+		)?;
+		#[extrinsic_call]
+		reset(RawOrigin::Root, TLD_NAME.to_vec());
+
+		assert!(LightClient::<T>::is_tracked(TLD_NAME));
+		Ok(())
+	}
+}
@@ -0,0 +1,272 @@
+//! Verification of GRANDPA justifications produced by TLD chains.
+
+use codec::{Decode, Encode};
+use frame_support::RuntimeDebug;
+use scale_info::{
+	prelude::{collections::btree_map::BTreeMap, vec::Vec},
+	TypeInfo,
+};
+use sp_consensus_grandpa::{AuthorityId, AuthorityList, AuthoritySignature, SetId};
+use sp_core::H256;
+use sp_runtime::{
+	generic,
+	traits::{BlakeTwo256, Header as HeaderT},
+	RuntimeAppPublic,
+};
+
+/// Header type of the TLD chains, which run the same node template.
+pub type TldHeader = generic::Header<u32, BlakeTwo256>;
+
+/// A GRANDPA justification as produced by `grandpa_proveFinality` on a TLD chain.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct GrandpaJustification {
+	pub round: u64,
+	pub commit: finality_grandpa::Commit<H256, u32, AuthoritySignature, AuthorityId>,
+	// Headers linking every precommit target to the commit target
+	pub votes_ancestries: Vec<TldHeader>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum JustificationError {
+	/// The justification does not finalize the given header.
+	InvalidTarget,
+	/// A precommit was signed by an account outside of the authority set.
+	UnknownAuthority,
+	/// A precommit signature does not match its authority.
+	InvalidSignature,
+	/// A precommit target does not descend from the commit target.
+	InvalidAncestry,
+	/// A precommit target number does not match its position in the ancestry.
+	InvalidTargetNumber,
+	/// The signed precommits do not reach the supermajority of the authority set.
+	TooFewVotes,
+}
+
+/// Checks that `justification` finalizes header `hash` at `number` with the votes of more than
+/// two thirds of `authorities` in set `set_id`.
+///
+/// Authorities that equivocated by signing several precommits are counted once, as GRANDPA
+/// includes their votes in the justifications it produces.
+pub fn verify_justification(
+	hash: H256,
+	number: u32,
+	authorities: &AuthorityList,
+	set_id: SetId,
+	justification: &GrandpaJustification,
+) -> Result<(), JustificationError> {
+	let commit = &justification.commit;
+	if commit.target_hash != hash || commit.target_number != number {
+		return Err(JustificationError::InvalidTarget);
+	}
+
+	// Child to parent links of all the headers the precommits may point to.
+	let ancestry: BTreeMap<H256, H256> = justification
+		.votes_ancestries
+		.iter()
+		.map(|header| (header.hash(), *header.parent_hash()))
+		.collect();
+
+	let mut signers: Vec<&AuthorityId> = Vec::new();
+	let mut votes: u64 = 0;
+
+	for signed in commit.precommits.iter() {
+		let weight = authorities
+			.iter()
+			.find(|(id, _)| id == &signed.id)
+			.map(|(_, weight)| *weight)
+			.ok_or(JustificationError::UnknownAuthority)?;
+
+		let message = finality_grandpa::Message::Precommit(signed.precommit.clone());
+		let payload =
+			sp_consensus_grandpa::localized_payload(justification.round, set_id, &message);
+		if !signed.id.verify(&payload, &signed.signature) {
+			return Err(JustificationError::InvalidSignature);
+		}
+
+		// Walk back from the precommit target until the commit target is reached.
+		let mut current = signed.precommit.target_hash;
+		let mut steps = 0u32;
+		while current != hash {
+			if steps as usize > ancestry.len() {
+				return Err(JustificationError::InvalidAncestry);
+			}
+			current = *ancestry.get(&current).ok_or(JustificationError::InvalidAncestry)?;
+			steps += 1;
+		}
+		if signed.precommit.target_number.checked_sub(number) != Some(steps) {
+			return Err(JustificationError::InvalidTargetNumber);
+		}
+
+		if !signers.contains(&&signed.id) {
+			signers.push(&signed.id);
+			votes = votes.saturating_add(weight);
+		}
+	}
+
+	let total: u64 = authorities.iter().map(|(_, weight)| *weight).sum();
+	let threshold = total - total.saturating_sub(1) / 3;
+	if votes < threshold {
+		return Err(JustificationError::TooFewVotes);
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::{ed25519, Pair};
+
+	const SET_ID: SetId = 3;
+	const ROUND: u64 = 7;
+
+	fn authority_keys(count: u8) -> Vec<ed25519::Pair> {
+		(0..count).map(|seed| ed25519::Pair::from_seed(&[seed; 32])).collect()
+	}
+
+	fn authorities(keys: &[ed25519::Pair]) -> AuthorityList {
+		keys.iter().map(|key| (AuthorityId::from(key.public()), 1)).collect()
+	}
+
+	/// The finalized header at 10 followed by `descendants` headers.
+	fn chain(descendants: u32) -> Vec<TldHeader> {
+		let mut headers: Vec<TldHeader> = Vec::new();
+		for number in 10..=10 + descendants {
+			let parent_hash = headers.last().map(|header| header.hash()).unwrap_or_default();
+			headers.push(TldHeader::new(
+				number,
+				Default::default(),
+				Default::default(),
+				parent_hash,
+				Default::default(),
+			));
+		}
+		headers
+	}
+
+	fn precommit(
+		key: &ed25519::Pair,
+		target: &TldHeader,
+	) -> finality_grandpa::SignedPrecommit<H256, u32, AuthoritySignature, AuthorityId> {
+		let precommit = finality_grandpa::Precommit {
+			target_hash: target.hash(),
+			target_number: target.number,
+		};
+		let message = finality_grandpa::Message::Precommit(precommit.clone());
+		let payload = sp_consensus_grandpa::localized_payload(ROUND, SET_ID, &message);
+		finality_grandpa::SignedPrecommit {
+			precommit,
+			signature: key.sign(&payload).into(),
+			id: key.public().into(),
+		}
+	}
+
+	/// A justification of `headers[0]` whose precommits vote for the given headers.
+	fn justify(headers: &[TldHeader], votes: &[(&ed25519::Pair, usize)]) -> GrandpaJustification {
+		GrandpaJustification {
+			round: ROUND,
+			commit: finality_grandpa::Commit {
+				target_hash: headers[0].hash(),
+				target_number: headers[0].number,
+				precommits: votes.iter().map(|(key, at)| precommit(key, &headers[*at])).collect(),
+			},
+			votes_ancestries: headers[1..].to_vec(),
+		}
+	}
+
+	fn verify(
+		headers: &[TldHeader],
+		keys: &[ed25519::Pair],
+		justification: &GrandpaJustification,
+	) -> Result<(), JustificationError> {
+		verify_justification(headers[0].hash(), 10, &authorities(keys), SET_ID, justification)
+	}
+
+	#[test]
+	fn accepts_supermajority_of_precommits() {
+		let (keys, headers) = (authority_keys(4), chain(2));
+		// Precommits may vote for descendants of the finalized header
+		let justification = justify(&headers, &[(&keys[0], 0), (&keys[1], 1), (&keys[2], 2)]);
+		assert_eq!(verify(&headers, &keys, &justification), Ok(()));
+	}
+
+	#[test]
+	fn rejects_too_few_votes() {
+		let (keys, headers) = (authority_keys(4), chain(0));
+		let justification = justify(&headers, &[(&keys[0], 0), (&keys[1], 0)]);
+		assert_eq!(verify(&headers, &keys, &justification), Err(JustificationError::TooFewVotes));
+	}
+
+	#[test]
+	fn counts_equivocating_authorities_once() {
+		let (keys, headers) = (authority_keys(4), chain(1));
+
+		let justification =
+			justify(&headers, &[(&keys[0], 0), (&keys[0], 1), (&keys[1], 0), (&keys[2], 1)]);
+		assert_eq!(verify(&headers, &keys, &justification), Ok(()));
+
+		let justification = justify(&headers, &[(&keys[0], 0), (&keys[0], 1), (&keys[1], 0)]);
+		assert_eq!(verify(&headers, &keys, &justification), Err(JustificationError::TooFewVotes));
+	}
+
+	#[test]
+	fn rejects_other_targets() {
+		let (keys, headers) = (authority_keys(1), chain(1));
+		let justification = justify(&headers, &[(&keys[0], 0)]);
+		assert_eq!(
+			verify_justification(
+				headers[1].hash(),
+				11,
+				&authorities(&keys),
+				SET_ID,
+				&justification
+			),
+			Err(JustificationError::InvalidTarget)
+		);
+	}
+
+	#[test]
+	fn rejects_precommit_target_numbers_off_the_ancestry() {
+		let (keys, headers) = (authority_keys(1), chain(1));
+		let mut justification = justify(&headers, &[(&keys[0], 1)]);
+		let signed = &mut justification.commit.precommits[0];
+		signed.precommit.target_number = 12;
+		let message = finality_grandpa::Message::Precommit(signed.precommit.clone());
+		let payload = sp_consensus_grandpa::localized_payload(ROUND, SET_ID, &message);
+		signed.signature = keys[0].sign(&payload).into();
+
+		assert_eq!(
+			verify(&headers, &keys, &justification),
+			Err(JustificationError::InvalidTargetNumber)
+		);
+	}
+
+	#[test]
+	fn rejects_precommits_outside_the_ancestry() {
+		let (keys, headers) = (authority_keys(1), chain(2));
+		let mut justification = justify(&headers, &[(&keys[0], 2)]);
+		justification.votes_ancestries.remove(0);
+		assert_eq!(
+			verify(&headers, &keys, &justification),
+			Err(JustificationError::InvalidAncestry)
+		);
+	}
+
+	#[test]
+	fn rejects_bad_signatures_and_unknown_authorities() {
+		let (keys, headers) = (authority_keys(2), chain(0));
+
+		let mut justification = justify(&headers, &[(&keys[0], 0)]);
+		justification.round += 1;
+		assert_eq!(
+			verify(&headers, &keys[..1], &justification),
+			Err(JustificationError::InvalidSignature)
+		);
+
+		let justification = justify(&headers, &[(&keys[1], 0)]);
+		assert_eq!(
+			verify(&headers, &keys[..1], &justification),
+			Err(JustificationError::UnknownAuthority)
+		);
+	}
+}
@@ -0,0 +1,370 @@
+//! # Light Client Pallet
+//!
+//! Tracks the finalized headers of registered TLD chains by importing their GRANDPA
+//! justifications, starting from the checkpoint recorded with the TLD in `pallet_rootdns`.
+//!
+//! Other pallets use the tracked state roots to check storage proofs taken from TLD chains
+//! without trusting the RPC node that served them.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod justification;
+pub mod weights;
+pub use weights::*;
+
+pub use justification::{GrandpaJustification, TldHeader};
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use crate::justification::verify_justification;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use scale_info::prelude::vec::Vec;
+	use sp_consensus_grandpa::{AuthorityList, ConsensusLog, SetId, GRANDPA_ENGINE_ID};
+	use sp_core::H256;
+	use sp_runtime::{traits::Header as HeaderT, DigestItem};
+
+	#[pallet::pallet]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching runtime event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		type PalletRootDNS: pallet_rootdns::Config;
+		/// Number of finalized headers kept per TLD chain before the oldest are pruned.
+		#[pallet::constant]
+		type HeadersToKeep: Get<u32>;
+		/// A type representing the weights required by the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct AuthoritySet {
+		pub authorities: AuthorityList,
+		pub set_id: SetId,
+	}
+
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct StoredHeader {
+		pub number: u32,
+		pub state_root: H256,
+	}
+
+	#[pallet::storage]
+	#[pallet::getter(fn authority_set)]
+	pub(super) type CurrentAuthoritySet<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		// The TLD name
+		Vec<u8>,
+		AuthoritySet,
+		OptionQuery,
+	>;
+
+	/// An authority set change signalled by a finalized header, waiting to be enacted.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct PendingChange {
+		pub next_authorities: AuthorityList,
+		// Number of the header from which the next authorities finalize the chain
+		pub enact_at: u32,
+	}
+
+	#[pallet::storage]
+	#[pallet::getter(fn pending_change)]
+	pub(super) type PendingAuthoritySetChange<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		// The TLD name
+		Vec<u8>,
+		PendingChange,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn best_finalized)]
+	pub(super) type BestFinalized<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		// The TLD name
+		Vec<u8>,
+		// Number and hash of the latest imported header
+		(u32, H256),
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn imported_header)]
+	pub(super) type ImportedHeaders<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		// The TLD name
+		Vec<u8>,
+		Identity,
+		// Header hash
+		H256,
+		StoredHeader,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	pub(super) type ImportedHashes<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		// The TLD name
+		Vec<u8>,
+		Twox64Concat,
+		// Slot in the ring buffer of imported headers
+		u32,
+		H256,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	pub(super) type ImportedHashesPointer<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, u32, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		LightClientInitialized { tld_name: Vec<u8>, header_hash: H256, number: u32 },
+		LightClientReset { tld_name: Vec<u8>, header_hash: H256, number: u32 },
+		HeaderImported { tld_name: Vec<u8>, header_hash: H256, number: u32 },
+		AuthoritySetChangeScheduled { tld_name: Vec<u8>, enact_at: u32 },
+		AuthoritySetChanged { tld_name: Vec<u8>, set_id: SetId },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Requested TLD was not found
+		TLDNotFound,
+		/// The TLD has no finality checkpoint to start from
+		NoCheckpoint,
+		/// The light client of this TLD was already initialized
+		AlreadyInitialized,
+		/// The light client of this TLD was not initialized
+		NotInitialized,
+		/// The header is not newer than the best finalized header
+		OldHeader,
+		/// The justification does not finalize the header with the current authority set
+		InvalidJustification,
+		/// The header schedules an authority set change that cannot be followed
+		UnsupportedAuthoritySetChange,
+		/// The header skips the header enacting the pending authority set change
+		PendingChangeNotEnacted,
+		/// The ancestry does not link the header to the best finalized header
+		InvalidAncestry,
+	}
+
+	// Exposing API functions for other pallets to use
+	impl<T: Config> Pallet<T> {
+		/// Whether the finality of the TLD chain is being followed.
+		pub fn is_tracked(tld: &[u8]) -> bool {
+			CurrentAuthoritySet::<T>::contains_key(tld.to_vec())
+		}
+
+		/// The number and state root of finalized header `hash` of the TLD chain, if it is still
+		/// stored.
+		pub fn finalized_header(tld: &[u8], hash: H256) -> Option<StoredHeader> {
+			ImportedHeaders::<T>::get(tld.to_vec(), hash)
+		}
+
+		fn import_header(tld_name: &Vec<u8>, hash: H256, number: u32, state_root: H256) {
+			// Overwrite the oldest slot of the ring buffer and prune the header it pointed to.
+			let pointer = ImportedHashesPointer::<T>::get(tld_name);
+			if let Some(pruned) = ImportedHashes::<T>::get(tld_name, pointer) {
+				ImportedHeaders::<T>::remove(tld_name, pruned);
+			}
+			ImportedHashes::<T>::insert(tld_name, pointer, hash);
+			ImportedHashesPointer::<T>::insert(
+				tld_name,
+				(pointer + 1) % T::HeadersToKeep::get().max(1),
+			);
+
+			ImportedHeaders::<T>::insert(tld_name, hash, StoredHeader { number, state_root });
+			BestFinalized::<T>::insert(tld_name, (number, hash));
+		}
+
+		/// Extracts the authority set change signalled by a finalized header, if any.
+		///
+		/// Forced changes are only issued when finality stalls, so they cannot be followed from
+		/// justifications and require the TLD checkpoint to be updated and the client reset.
+		fn scheduled_change(
+			header: &TldHeader,
+			number: u32,
+		) -> Result<Option<PendingChange>, Error<T>> {
+			let mut change = None;
+
+			for log in header.digest().logs() {
+				let DigestItem::Consensus(engine_id, data) = log else { continue };
+				if *engine_id != GRANDPA_ENGINE_ID {
+					continue;
+				}
+
+				match ConsensusLog::<u32>::decode(&mut &data[..]) {
+					Ok(ConsensusLog::ScheduledChange(scheduled)) =>
+						change = Some(PendingChange {
+							next_authorities: scheduled.next_authorities,
+							enact_at: number.saturating_add(scheduled.delay),
+						}),
+					Ok(ConsensusLog::ForcedChange(_, _)) =>
+						return Err(Error::<T>::UnsupportedAuthoritySetChange),
+					_ => {},
+				}
+			}
+
+			Ok(change)
+		}
+
+		/// Follows the authority set of the TLD chain from the checkpoint recorded in
+		/// `pallet_rootdns`, dropping any pending change.
+		fn start_from_checkpoint(tld_name: &Vec<u8>) -> Result<(H256, u32), Error<T>> {
+			let tld_info =
+				pallet_rootdns::Pallet::<T::PalletRootDNS>::get_chainspec_for_tld(tld_name)
+					.ok_or(Error::<T>::TLDNotFound)?;
+			let checkpoint = tld_info.checkpoint.ok_or(Error::<T>::NoCheckpoint)?;
+
+			CurrentAuthoritySet::<T>::insert(
+				tld_name,
+				AuthoritySet { authorities: checkpoint.authorities, set_id: checkpoint.set_id },
+			);
+			PendingAuthoritySetChange::<T>::remove(tld_name);
+			Self::import_header(
+				tld_name,
+				checkpoint.header_hash,
+				checkpoint.number,
+				checkpoint.state_root,
+			);
+
+			Ok((checkpoint.header_hash, checkpoint.number))
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Starts following the finality of a TLD chain from the checkpoint of its TLD.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::initialize())]
+		pub fn initialize(origin: OriginFor<T>, tld_name: Vec<u8>) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			ensure!(!Self::is_tracked(&tld_name), Error::<T>::AlreadyInitialized);
+
+			let (header_hash, number) = Self::start_from_checkpoint(&tld_name)?;
+			Self::deposit_event(Event::LightClientInitialized { tld_name, header_hash, number });
+
+			Ok(())
+		}
+
+		/// Imports a finalized header of a TLD chain along with its GRANDPA justification.
+		///
+		/// `ancestry` holds every header between the best finalized header and `header`, oldest
+		/// first, so that no authority set change signalled in between goes unnoticed. The headers
+		/// at which changes are enacted must be imported for the light client to keep following
+		/// the chain.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::submit_finality_proof(
+			justification.commit.precommits.len() as u32,
+			justification.votes_ancestries.len() as u32,
+			ancestry.len() as u32,
+		))]
+		pub fn submit_finality_proof(
+			origin: OriginFor<T>,
+			tld_name: Vec<u8>,
+			header: TldHeader,
+			justification: GrandpaJustification,
+			ancestry: Vec<TldHeader>,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			let mut authority_set =
+				Self::authority_set(&tld_name).ok_or(Error::<T>::NotInitialized)?;
+			let (best_number, best_hash) =
+				Self::best_finalized(&tld_name).ok_or(Error::<T>::NotInitialized)?;
+
+			let number = *header.number();
+			ensure!(number > best_number, Error::<T>::OldHeader);
+			ensure!(ancestry.len() as u32 == number - best_number - 1, Error::<T>::InvalidAncestry);
+
+			// Follow the chain from the best finalized header, picking up the changes it signals.
+			// The header enacting a pending change is the last one finalized by the current set.
+			let mut pending = Self::pending_change(&tld_name);
+			let mut hash = best_hash;
+			for ancestor in ancestry.iter().chain(core::iter::once(&header)) {
+				ensure!(*ancestor.parent_hash() == hash, Error::<T>::InvalidAncestry);
+				hash = ancestor.hash();
+
+				let ancestor_number = *ancestor.number();
+				if let Some(pending) = &pending {
+					ensure!(
+						ancestor_number <= pending.enact_at,
+						Error::<T>::PendingChangeNotEnacted
+					);
+				}
+
+				if let Some(scheduled) = Self::scheduled_change(ancestor, ancestor_number)? {
+					// GRANDPA does not signal a change while another one is pending on the chain.
+					ensure!(pending.is_none(), Error::<T>::UnsupportedAuthoritySetChange);
+					Self::deposit_event(Event::AuthoritySetChangeScheduled {
+						tld_name: tld_name.clone(),
+						enact_at: scheduled.enact_at,
+					});
+					pending = Some(scheduled);
+				}
+			}
+
+			verify_justification(
+				hash,
+				number,
+				&authority_set.authorities,
+				authority_set.set_id,
+				&justification,
+			)
+			.map_err(|err| {
+				log::info!("Rejected justification for {:?}: {:?}", hash, err);
+				Error::<T>::InvalidJustification
+			})?;
+
+			match pending {
+				Some(pending) if pending.enact_at == number => {
+					authority_set.authorities = pending.next_authorities;
+					authority_set.set_id = authority_set.set_id.saturating_add(1);
+					CurrentAuthoritySet::<T>::insert(&tld_name, &authority_set);
+					PendingAuthoritySetChange::<T>::remove(&tld_name);
+					Self::deposit_event(Event::AuthoritySetChanged {
+						tld_name: tld_name.clone(),
+						set_id: authority_set.set_id,
+					});
+				},
+				Some(pending) => PendingAuthoritySetChange::<T>::insert(&tld_name, pending),
+				None => {},
+			}
+
+			Self::import_header(&tld_name, hash, number, *header.state_root());
+			Self::deposit_event(Event::HeaderImported { tld_name, header_hash: hash, number });
+
+			Ok(())
+		}
+
+		/// Restarts following a TLD chain from the checkpoint of its TLD, after the checkpoint was
+		/// updated past an authority set change that could not be followed.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::reset())]
+		pub fn reset(origin: OriginFor<T>, tld_name: Vec<u8>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			ensure!(Self::is_tracked(&tld_name), Error::<T>::NotInitialized);
+
+			let (header_hash, number) = Self::start_from_checkpoint(&tld_name)?;
+			Self::deposit_event(Event::LightClientReset { tld_name, header_hash, number });
+
+			Ok(())
+		}
+	}
+}
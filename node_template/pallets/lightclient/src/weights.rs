@@ -0,0 +1,94 @@
+//! Weights for pallet_lightclient
+//!
+//! The signature and hashing costs are estimated from the GRANDPA and BLAKE2 benchmarks of the
+//! node template until this pallet is benchmarked on reference hardware with:
+//!
+//! ../../target/release/node-template benchmark pallet --chain dev --pallet pallet_lightclient
+//! --extrinsic * --steps=50 --repeat=20 --wasm-execution=compiled
+//! --output pallets/lightclient/src/weights.rs --template ../../.maintain/frame-weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_lightclient.
+pub trait WeightInfo {
+	fn initialize() -> Weight;
+	fn submit_finality_proof(p: u32, a: u32, h: u32, ) -> Weight;
+	fn reset() -> Weight;
+}
+
+/// Weights for pallet_lightclient using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: LightClientModule CurrentAuthoritySet (r:1 w:1)
+	/// Storage: RootDNSModule TLDMap (r:1 w:0)
+	/// Storage: LightClientModule PendingAuthoritySetChange (r:0 w:1)
+	/// Storage: LightClientModule ImportedHashesPointer (r:1 w:1)
+	/// Storage: LightClientModule ImportedHashes (r:1 w:1)
+	/// Storage: LightClientModule ImportedHeaders (r:0 w:2)
+	/// Storage: LightClientModule BestFinalized (r:0 w:1)
+	fn initialize() -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
+	/// Storage: LightClientModule CurrentAuthoritySet (r:1 w:1)
+	/// Storage: LightClientModule BestFinalized (r:1 w:1)
+	/// Storage: LightClientModule PendingAuthoritySetChange (r:1 w:1)
+	/// Storage: LightClientModule ImportedHashesPointer (r:1 w:1)
+	/// Storage: LightClientModule ImportedHashes (r:1 w:1)
+	/// Storage: LightClientModule ImportedHeaders (r:0 w:2)
+	/// The range of component `p` is `[1, 256]`: precommits of the justification.
+	/// The range of component `a` is `[0, 256]`: vote ancestries of the justification.
+	/// The range of component `h` is `[0, 256]`: headers between the best finalized header and the
+	/// imported one.
+	fn submit_finality_proof(p: u32, a: u32, h: u32, ) -> Weight {
+		Weight::from_parts(25_000_000, 0)
+			// Signature check of each precommit
+			.saturating_add(Weight::from_parts(50_000_000, 0).saturating_mul(p.into()))
+			// Walk of each precommit back through the vote ancestries
+			.saturating_add(Weight::from_parts(100_000, 0).saturating_mul((p as u64).saturating_mul(a.into())))
+			// Hashing of each vote ancestry and each skipped header
+			.saturating_add(Weight::from_parts(2_000_000, 0).saturating_mul((a as u64).saturating_add(h.into())))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
+	/// Storage: LightClientModule CurrentAuthoritySet (r:1 w:1)
+	/// Storage: RootDNSModule TLDMap (r:1 w:0)
+	/// Storage: LightClientModule PendingAuthoritySetChange (r:0 w:1)
+	/// Storage: LightClientModule ImportedHashesPointer (r:1 w:1)
+	/// Storage: LightClientModule ImportedHashes (r:1 w:1)
+	/// Storage: LightClientModule ImportedHeaders (r:0 w:2)
+	/// Storage: LightClientModule BestFinalized (r:0 w:1)
+	fn reset() -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn initialize() -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
+	fn submit_finality_proof(p: u32, a: u32, h: u32, ) -> Weight {
+		Weight::from_parts(25_000_000, 0)
+			.saturating_add(Weight::from_parts(50_000_000, 0).saturating_mul(p.into()))
+			.saturating_add(Weight::from_parts(100_000, 0).saturating_mul((p as u64).saturating_mul(a.into())))
+			.saturating_add(Weight::from_parts(2_000_000, 0).saturating_mul((a as u64).saturating_add(h.into())))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
+	fn reset() -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
+}
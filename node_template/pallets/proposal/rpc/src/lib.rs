@@ -0,0 +1,147 @@
+//! RPC interface for the proposal pallet.
+//!
+//! Exposes the [`ProposalRuntimeApi`] under the `proposal_*` namespace.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use serde::{de::DeserializeOwned, Serialize};
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{ErrorObject, ErrorObjectOwned},
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::traits::Block as BlockT;
+
+pub use pallet_proposal_runtime_api::{ProposalApi as ProposalRuntimeApi, ProposalInfo};
+
+/// Largest page a single listing call returns.
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Error code returned when the runtime API call fails.
+const RUNTIME_ERROR: i32 = 1;
+
+#[rpc(client, server)]
+pub trait ProposalApi<BlockHash, AccountId, Balance, BlockNumber> {
+	/// Lists open proposals, starting after the given domain name.
+	#[method(name = "proposal_openProposals")]
+	fn open_proposals(
+		&self,
+		start_after: Option<Bytes>,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(Bytes, ProposalInfo<AccountId, Balance, BlockNumber>)>>;
+
+	/// Lists closed proposals, starting after the given domain name.
+	#[method(name = "proposal_finishedProposals")]
+	fn finished_proposals(
+		&self,
+		start_after: Option<Bytes>,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(Bytes, ProposalInfo<AccountId, Balance, BlockNumber>)>>;
+
+	/// Returns the `(ayes, nays)` tally of a proposal.
+	#[method(name = "proposal_tally")]
+	fn proposal_tally(&self, domain_name: Bytes, at: Option<BlockHash>)
+		-> RpcResult<Option<(u64, u64)>>;
+
+	/// Lists the direct votes of an account, starting after the given domain name.
+	#[method(name = "proposal_accountVotes")]
+	fn account_votes(
+		&self,
+		who: AccountId,
+		start_after: Option<Bytes>,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(Bytes, bool)>>;
+}
+
+/// Provides RPC methods to query domain proposals.
+pub struct Proposal<C, Block> {
+	client: Arc<C>,
+	_marker: PhantomData<Block>,
+}
+
+impl<C, Block> Proposal<C, Block> {
+	/// Creates a new instance of the Proposal RPC helper.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+fn runtime_error(err: impl std::fmt::Debug) -> ErrorObjectOwned {
+	ErrorObject::owned(RUNTIME_ERROR, "Unable to query proposals.", Some(format!("{:?}", err)))
+}
+
+fn into_bytes<V>(entries: Vec<(Vec<u8>, V)>) -> Vec<(Bytes, V)> {
+	entries.into_iter().map(|(key, value)| (key.into(), value)).collect()
+}
+
+impl<C, Block, AccountId, Balance, BlockNumber>
+	ProposalApiServer<<Block as BlockT>::Hash, AccountId, Balance, BlockNumber> for Proposal<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: ProposalRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+	AccountId: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+	Balance: Codec + Serialize + Send + Sync + 'static,
+	BlockNumber: Codec + Serialize + Send + Sync + 'static,
+{
+	fn open_proposals(
+		&self,
+		start_after: Option<Bytes>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(Bytes, ProposalInfo<AccountId, Balance, BlockNumber>)>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.open_proposals(at, start_after.map(|b| b.to_vec()), limit.min(MAX_PAGE_SIZE))
+			.map(into_bytes)
+			.map_err(runtime_error)
+	}
+
+	fn finished_proposals(
+		&self,
+		start_after: Option<Bytes>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(Bytes, ProposalInfo<AccountId, Balance, BlockNumber>)>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.finished_proposals(at, start_after.map(|b| b.to_vec()), limit.min(MAX_PAGE_SIZE))
+			.map(into_bytes)
+			.map_err(runtime_error)
+	}
+
+	fn proposal_tally(
+		&self,
+		domain_name: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<(u64, u64)>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.proposal_tally(at, domain_name.to_vec()).map_err(runtime_error)
+	}
+
+	fn account_votes(
+		&self,
+		who: AccountId,
+		start_after: Option<Bytes>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(Bytes, bool)>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.account_votes(at, who, start_after.map(|b| b.to_vec()), limit.min(MAX_PAGE_SIZE))
+			.map(into_bytes)
+			.map_err(runtime_error)
+	}
+}
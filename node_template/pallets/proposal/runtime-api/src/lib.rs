@@ -0,0 +1,37 @@
+//! Runtime API definition for the proposal pallet.
+//!
+//! Lets clients list domain proposals and votes without knowing the proposed domain names.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_proposal::ProposalInfo;
+
+sp_api::decl_runtime_apis! {
+	pub trait ProposalApi<AccountId, Balance, BlockNumber>
+	where
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// Proposals still open for voting, ordered by storage key, starting after `start_after`.
+		fn open_proposals(
+			start_after: Option<Vec<u8>>,
+			limit: u32,
+		) -> Vec<(Vec<u8>, ProposalInfo<AccountId, Balance, BlockNumber>)>;
+
+		/// Proposals that were closed, ordered by storage key, starting after `start_after`.
+		fn finished_proposals(
+			start_after: Option<Vec<u8>>,
+			limit: u32,
+		) -> Vec<(Vec<u8>, ProposalInfo<AccountId, Balance, BlockNumber>)>;
+
+		/// The `(ayes, nays)` tally of a proposal, including delegated weight.
+		fn proposal_tally(domain_name: Vec<u8>) -> Option<(u64, u64)>;
+
+		/// The direct votes cast by `who`, ordered by proposal storage key.
+		fn account_votes(who: AccountId, start_after: Option<Vec<u8>>, limit: u32) -> Vec<(Vec<u8>, bool)>;
+	}
+}
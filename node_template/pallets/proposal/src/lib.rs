@@ -61,20 +61,42 @@ pub mod weights;
 
 pub use weights::*;
 
+pub mod migrations;
+
 // All pallet logic is defined in its own module and must be annotated by the `pallet` attribute.
 #[frame_support::pallet]
 pub mod pallet {
     // Import various useful types required by all FRAME pallets.
     use super::*;
-    use frame_support::pallet_prelude::*;
+    use frame_support::{
+        pallet_prelude::*,
+        traits::{BalanceStatus, Currency, QueryPreimage, ReservableCurrency, StorePreimage},
+    };
+    #[cfg(feature = "std")]
+    use frame_support::{Deserialize, Serialize};
     use frame_system::pallet_prelude::*;
+    use scale_info::prelude::{collections::BTreeMap, vec::Vec};
+    use sp_runtime::{
+        traits::{Hash, IdentifyAccount, Verify, Zero},
+        Perbill,
+    };
+
+    /// The in-code storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+    // Rough cost of verifying the signature of one off-chain vote
+    const SIGNATURE_WEIGHT: u64 = 50_000_000;
 
     // The `Pallet` struct serves as a placeholder to implement traits, methods and dispatchables
     // (`Call`s) in this pallet.
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     #[pallet::without_storage_info]
     pub struct Pallet<T>(_);
 
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
     /// The pallet's configuration trait.
     ///
     /// All our types and constants a pallet depends on must be declared here.
@@ -86,10 +108,144 @@ pub mod pallet {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
         /// A type representing the weights required by the dispatchables of this pallet.
         type WeightInfo: WeightInfo;
+        /// The currency used to reserve proposal bonds.
+        type Currency: ReservableCurrency<Self::AccountId>;
+        /// The amount reserved from the proposer when a domain is proposed.
+        #[pallet::constant]
+        type ProposalBond: Get<BalanceOf<Self>>;
+        /// Number of blocks a proposal stays open for voting.
+        #[pallet::constant]
+        type VotingPeriod: Get<BlockNumberFor<Self>>;
+        /// Share of positive votes required for a proposal to be approved.
+        #[pallet::constant]
+        type ApprovalThreshold: Get<Perbill>;
+        /// Share of positive votes below which a proposal is considered spam and its bond slashed.
+        #[pallet::constant]
+        type SpamThreshold: Get<Perbill>;
+        /// The account receiving slashed bonds.
+        #[pallet::constant]
+        type TreasuryAccount: Get<Self::AccountId>;
+        /// Maximum length of a delegation chain counted in tallies.
+        #[pallet::constant]
+        type MaxDelegationDepth: Get<u32>;
+        /// Maximum number of accounts that can delegate directly to a single account.
+        #[pallet::constant]
+        type MaxDelegators: Get<u32>;
+        /// Signature used by voters to sign votes off-chain.
+        type OffchainSignature: Verify<Signer = Self::OffchainPublic> + Parameter;
+        /// Public key matching `OffchainSignature`, convertible into a voter account.
+        type OffchainPublic: IdentifyAccount<AccountId = Self::AccountId>;
+        /// Maximum number of signed votes a relayer can submit in a single batch.
+        #[pallet::constant]
+        type MaxSignedVotes: Get<u32>;
+        /// The preimage provider holding proposal rationales too large to store inline.
+        type Preimages: QueryPreimage<H = Self::Hashing> + StorePreimage;
+        /// The base amount reserved for attaching metadata to a proposal.
+        #[pallet::constant]
+        type MetadataDepositBase: Get<BalanceOf<Self>>;
+        /// The amount reserved per byte of inline proposal metadata.
+        #[pallet::constant]
+        type MetadataDepositPerByte: Get<BalanceOf<Self>>;
+        /// Maximum length of inline proposal metadata.
+        #[pallet::constant]
+        type MaxMetadataLen: Get<u32>;
     }
 
     type VecSize = ConstU32<100>;
 
+    // Prefix of the message signed by voters, keeping vote signatures out of other contexts
+    const SIGNED_VOTE_PREFIX: &[u8] = b"bcdns/proposal/vote";
+
+    /// The state of a domain proposal.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    pub enum ProposalStatus {
+        /// Voting is still ongoing.
+        Open,
+        /// The proposal reached the approval threshold, the bond was returned.
+        Approved,
+        /// The proposal was rejected with enough support or without any vote, the bond was returned.
+        Rejected,
+        /// The proposal fell below the spam threshold, the bond was slashed.
+        Slashed,
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    pub struct ProposalInfo<AccountId, Balance, BlockNumber> {
+        // Account that opened the proposal and reserved the bond
+        pub proposer: AccountId,
+        // Amount reserved from the proposer
+        pub bond: Balance,
+        // Weight of positive votes, filled in when the proposal is closed
+        pub ayes: u64,
+        // Weight of negative votes, filled in when the proposal is closed
+        pub nays: u64,
+        // Last block at which votes are accepted
+        pub end: BlockNumber,
+        pub status: ProposalStatus,
+    }
+
+    /// Running aggregates of the delegation tree below an account.
+    #[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+    pub struct DelegationStats {
+        // Number of accounts delegating to the account, directly or transitively
+        pub weight: u64,
+        // Length of the longest delegation chain ending at the account, the account excluded
+        pub height: u32,
+    }
+
+    /// The rationale and supporting documents attached to a proposal.
+    #[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo)]
+    #[scale_info(skip_type_params(MaxLen))]
+    pub enum ProposalRationale<H: Clone + PartialEq + Eq + core::fmt::Debug, MaxLen: Get<u32>> {
+        /// Full text noted in `pallet_preimage`, requested for as long as it is attached.
+        Preimage { hash: H, len: u32 },
+        /// Full text stored on-chain next to its hash.
+        Inline { hash: H, data: BoundedVec<u8, MaxLen> },
+    }
+
+    impl<H: Clone + PartialEq + Eq + core::fmt::Debug, MaxLen: Get<u32>> ProposalRationale<H, MaxLen> {
+        pub fn hash(&self) -> &H {
+            match self {
+                Self::Preimage { hash, .. } | Self::Inline { hash, .. } => hash,
+            }
+        }
+    }
+
+    /// Where the rationale given to `set_proposal_metadata` comes from.
+    #[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo)]
+    #[scale_info(skip_type_params(MaxLen))]
+    pub enum MetadataSource<H: Clone + PartialEq + Eq + core::fmt::Debug, MaxLen: Get<u32>> {
+        /// Text stored inline, with a deposit per byte on top of the base deposit.
+        Inline(BoundedVec<u8, MaxLen>),
+        /// Hash of a document noted in `pallet_preimage`, only reserving the base deposit.
+        Preimage(H),
+    }
+
+    #[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo)]
+    #[scale_info(skip_type_params(T))]
+    pub struct ProposalMetadata<T: Config> {
+        pub rationale: ProposalRationale<T::Hash, T::MaxMetadataLen>,
+        // Amount reserved from the proposer for this metadata
+        pub deposit: BalanceOf<T>,
+    }
+
+    /// A vote signed off-chain by `voter` and relayed on-chain by any account.
+    ///
+    /// The signature covers `(SIGNED_VOTE_PREFIX, genesis_hash, domain_name, vote, nonce)`
+    /// SCALE-encoded, where `genesis_hash` is the hash of block 0 of the chain.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+    pub struct SignedVote<AccountId, Signature> {
+        pub voter: AccountId,
+        // Domain name identifying the proposal
+        pub domain_name: BoundedVec<u8, VecSize>,
+        pub vote: bool,
+        // Must match the voter's current nonce in `VoteNonces`
+        pub nonce: u64,
+        pub signature: Signature,
+    }
+
     /// A storage item for this pallet.
     ///
     /// In this template, we are declaring a storage item called `Something` that stores a single
@@ -101,8 +257,8 @@ pub mod pallet {
         Blake2_128Concat,
         // Proposed domain name
         BoundedVec<u8, VecSize>,
-        // Number of votes cast
-        u64,
+        // Proposer, bond and votes cast
+        ProposalInfo<T::AccountId, BalanceOf<T>, BlockNumberFor<T>>,
         OptionQuery
     >;
 
@@ -121,6 +277,78 @@ pub mod pallet {
         ValueQuery
     >;
 
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_metadata)]
+    pub(super) type ProposalMetadataMap<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        // Proposed domain name
+        BoundedVec<u8, VecSize>,
+        // Reference to the rationale and the deposit backing it
+        ProposalMetadata<T>,
+        OptionQuery
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn vote_nonces)]
+    pub(super) type VoteNonces<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        // Voter account ID
+        T::AccountId,
+        // Nonce expected in the voter's next signed vote
+        u64,
+        ValueQuery
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn delegations)]
+    pub(super) type Delegations<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        // Delegating account ID
+        T::AccountId,
+        // Account voting on its behalf
+        T::AccountId,
+        OptionQuery
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn delegators)]
+    pub(super) type Delegators<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        // Delegate account ID
+        T::AccountId,
+        // Accounts delegating directly to it
+        BoundedVec<T::AccountId, T::MaxDelegators>,
+        ValueQuery
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn delegation_stats)]
+    pub(super) type DelegationStatsMap<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        // Delegate account ID
+        T::AccountId,
+        // Weight and height of the delegation tree below it
+        DelegationStats,
+        ValueQuery
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn vote_count)]
+    pub(super) type VoteCount<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        // Proposed domain name
+        BoundedVec<u8, VecSize>,
+        // Number of direct votes cast on the proposal
+        u32,
+        ValueQuery
+    >;
+
     /// Events that functions in this pallet can emit.
     ///
     /// Events are a simple means of indicating to the outside world (such as dApps, chain explorers
@@ -137,12 +365,47 @@ pub mod pallet {
         DomainProposed {
             domain_name: BoundedVec<u8, VecSize>,
             creator: T::AccountId,
+            bond: BalanceOf<T>,
         },
         DomainVote {
             domain_name: BoundedVec<u8, VecSize>,
             voter: T::AccountId,
             vote: bool
         },
+        ProposalApproved {
+            domain_name: BoundedVec<u8, VecSize>,
+            ayes: u64,
+            nays: u64,
+        },
+        ProposalRejected {
+            domain_name: BoundedVec<u8, VecSize>,
+            ayes: u64,
+            nays: u64,
+        },
+        ProposalSlashed {
+            domain_name: BoundedVec<u8, VecSize>,
+            proposer: T::AccountId,
+            bond: BalanceOf<T>,
+        },
+        VoteDelegated {
+            delegator: T::AccountId,
+            delegate: T::AccountId,
+        },
+        VoteUndelegated {
+            delegator: T::AccountId,
+        },
+        SignedVoteRejected {
+            domain_name: BoundedVec<u8, VecSize>,
+            voter: T::AccountId,
+            nonce: u64,
+        },
+        ProposalMetadataSet {
+            domain_name: BoundedVec<u8, VecSize>,
+            hash: T::Hash,
+        },
+        ProposalMetadataCleared {
+            domain_name: BoundedVec<u8, VecSize>,
+        },
     }
 
     /// Errors that can be returned by this pallet.
@@ -165,6 +428,282 @@ pub mod pallet {
         ProposalNotFound,
         /// The user has already cast a vote for this domain
         UserAlreadyVoted,
+        /// The proposer cannot afford the proposal bond
+        InsufficientBond,
+        /// The voting period of the proposal is over
+        VotingClosed,
+        /// The voting period of the proposal has not ended yet
+        VotingStillOpen,
+        /// The proposal was already closed
+        ProposalNotOpen,
+        /// An account cannot delegate to itself
+        SelfDelegation,
+        /// The delegation would create a cycle
+        DelegationCycle,
+        /// The delegation chain would exceed the maximum depth
+        DelegationTooDeep,
+        /// The delegate already has the maximum number of delegators
+        TooManyDelegators,
+        /// The account is not delegating its vote
+        NotDelegating,
+        /// Only the proposer can change the metadata of a proposal
+        NotProposer,
+        /// The referenced preimage was not noted
+        PreimageNotFound,
+        /// The proposal has no metadata attached
+        NoMetadata,
+        /// The number of voters given is lower than the number of votes cast
+        InvalidVoterCount,
+    }
+
+    impl<T: Config> Pallet<T> {
+        fn do_vote(voter: T::AccountId,
+                   domain_name: BoundedVec<u8, VecSize>,
+                   vote: bool)
+                   -> DispatchResult {
+            let proposal = Self::proposal_map(&domain_name).ok_or(Error::<T>::ProposalNotFound)?;
+            ensure!(proposal.status == ProposalStatus::Open, Error::<T>::ProposalNotOpen);
+            ensure!(<frame_system::Pallet<T>>::block_number() <= proposal.end, Error::<T>::VotingClosed);
+            ensure!(!<VoteMap<T>>::contains_key(&domain_name, &voter), Error::<T>::UserAlreadyVoted);
+
+            // Delegated weight is resolved when the proposal is tallied.
+            <VoteMap<T>>::insert(&domain_name, &voter, &vote);
+            <VoteCount<T>>::mutate(&domain_name, |count| *count = count.saturating_add(1));
+            // Emit an event.
+            Self::deposit_event(Event::DomainVote { domain_name, voter, vote });
+
+            Ok(())
+        }
+
+        /// Checks the nonce and signature of an off-chain vote.
+        fn check_signed_vote(
+            signed_vote: &SignedVote<T::AccountId, T::OffchainSignature>,
+        ) -> bool {
+            if signed_vote.nonce != Self::vote_nonces(&signed_vote.voter) {
+                return false;
+            }
+
+            // Binding the genesis hash keeps votes signed for another chain from being replayed here.
+            let genesis_hash = <frame_system::Pallet<T>>::block_hash(BlockNumberFor::<T>::zero());
+            let message = (
+                SIGNED_VOTE_PREFIX,
+                genesis_hash,
+                &signed_vote.domain_name,
+                signed_vote.vote,
+                signed_vote.nonce,
+            )
+                .encode();
+            signed_vote.signature.verify(&message[..], &signed_vote.voter)
+        }
+
+        /// Computes the current tally of a proposal as `(ayes, nays)`.
+        ///
+        /// Every direct vote carries the weight of the voter plus that of all accounts delegating
+        /// to it, transitively, minus the subtrees of delegators that voted on the proposal
+        /// themselves. Reads at most `MaxDelegationDepth + 2` entries per direct vote.
+        pub fn tally(domain_name: &BoundedVec<u8, VecSize>) -> (u64, u64) {
+            let votes: BTreeMap<T::AccountId, bool> =
+                <VoteMap<T>>::iter_prefix(domain_name).collect();
+            let mut weights: BTreeMap<T::AccountId, u64> = votes
+                .keys()
+                .map(|voter| {
+                    (voter.clone(), 1u64.saturating_add(Self::delegation_stats(voter).weight))
+                })
+                .collect();
+
+            // A direct vote by a delegator overrides the one of its closest voting delegate, which
+            // loses the whole subtree of the delegator.
+            for voter in votes.keys() {
+                let subtree = 1u64.saturating_add(Self::delegation_stats(voter).weight);
+                let mut current = Self::delegations(voter);
+                for _ in 0..T::MaxDelegationDepth::get() {
+                    let Some(delegate) = current else { break };
+                    if let Some(weight) = weights.get_mut(&delegate) {
+                        *weight = weight.saturating_sub(subtree);
+                        break;
+                    }
+                    current = Self::delegations(&delegate);
+                }
+            }
+
+            weights.into_iter().fold((0u64, 0u64), |(ayes, nays), (voter, weight)| {
+                if votes.get(&voter).copied().unwrap_or_default() {
+                    (ayes.saturating_add(weight), nays)
+                } else {
+                    (ayes, nays.saturating_add(weight))
+                }
+            })
+        }
+
+        /// Adds a subtree of `stats` newly delegating to `delegate` to the aggregates of every
+        /// account up the chain of `delegate`.
+        fn attach_subtree(delegate: &T::AccountId, stats: &DelegationStats) {
+            let mut current = Some(delegate.clone());
+            let mut height = stats.height.saturating_add(1);
+            for _ in 0..=T::MaxDelegationDepth::get() {
+                let Some(account) = current else { break };
+                <DelegationStatsMap<T>>::mutate(&account, |account_stats| {
+                    account_stats.weight = account_stats.weight.saturating_add(stats.weight.saturating_add(1));
+                    account_stats.height = account_stats.height.max(height);
+                });
+                height = height.saturating_add(1);
+                current = Self::delegations(&account);
+            }
+        }
+
+        /// Removes a subtree of `stats` that stopped delegating to `delegate` from the aggregates
+        /// of every account up the chain of `delegate`.
+        ///
+        /// Heights are recomputed from the direct delegators of each account, so the subtree must
+        /// already be removed from `Delegators`.
+        fn detach_subtree(delegate: &T::AccountId, stats: &DelegationStats) {
+            let mut current = Some(delegate.clone());
+            for _ in 0..=T::MaxDelegationDepth::get() {
+                let Some(account) = current else { break };
+                let height = Self::delegators(&account)
+                    .iter()
+                    .map(|delegator| Self::delegation_stats(delegator).height.saturating_add(1))
+                    .max()
+                    .unwrap_or(0);
+                <DelegationStatsMap<T>>::mutate_exists(&account, |account_stats| {
+                    let mut updated = account_stats.take().unwrap_or_default();
+                    updated.weight = updated.weight.saturating_sub(stats.weight.saturating_add(1));
+                    updated.height = height;
+                    if updated != DelegationStats::default() {
+                        *account_stats = Some(updated);
+                    }
+                });
+                current = Self::delegations(&account);
+            }
+        }
+
+        /// Releases the deposit and preimage request of the metadata attached to a proposal.
+        fn release_metadata(
+            domain_name: &BoundedVec<u8, VecSize>,
+            proposer: &T::AccountId,
+        ) -> bool {
+            let Some(metadata) = <ProposalMetadataMap<T>>::take(domain_name) else {
+                return false;
+            };
+
+            if let ProposalRationale::Preimage { hash, .. } = &metadata.rationale {
+                T::Preimages::unrequest(hash);
+            }
+            T::Currency::unreserve(proposer, metadata.deposit);
+            true
+        }
+
+        /// Settles the bond of a proposal whose voting period is over and returns its final status.
+        fn settle_bond(
+            domain_name: &BoundedVec<u8, VecSize>,
+            proposal: &ProposalInfo<T::AccountId, BalanceOf<T>, BlockNumberFor<T>>,
+        ) -> ProposalStatus {
+            let turnout = proposal.ayes.saturating_add(proposal.nays);
+            let support = if turnout == 0 {
+                Perbill::zero()
+            } else {
+                Perbill::from_rational(proposal.ayes, turnout)
+            };
+
+            // Without any vote there is no evidence of spam, so the bond is returned.
+            if turnout > 0 && support < T::SpamThreshold::get() {
+                // Whatever could not be repatriated stays reserved on the proposer.
+                let _ = T::Currency::repatriate_reserved(
+                    &proposal.proposer,
+                    &T::TreasuryAccount::get(),
+                    proposal.bond,
+                    BalanceStatus::Free,
+                );
+                Self::deposit_event(Event::ProposalSlashed {
+                    domain_name: domain_name.clone(),
+                    proposer: proposal.proposer.clone(),
+                    bond: proposal.bond,
+                });
+                return ProposalStatus::Slashed;
+            }
+
+            T::Currency::unreserve(&proposal.proposer, proposal.bond);
+
+            if support >= T::ApprovalThreshold::get() {
+                Self::deposit_event(Event::ProposalApproved {
+                    domain_name: domain_name.clone(),
+                    ayes: proposal.ayes,
+                    nays: proposal.nays,
+                });
+                ProposalStatus::Approved
+            } else {
+                Self::deposit_event(Event::ProposalRejected {
+                    domain_name: domain_name.clone(),
+                    ayes: proposal.ayes,
+                    nays: proposal.nays,
+                });
+                ProposalStatus::Rejected
+            }
+        }
+    }
+
+    // Exposing API functions for the runtime API
+    impl<T: Config> Pallet<T> {
+        /// Lists proposals in storage order after `start_after`, keeping those that are open when
+        /// `open` is true and finished ones otherwise.
+        pub fn proposals(
+            open: bool,
+            start_after: Option<Vec<u8>>,
+            limit: u32,
+        ) -> Vec<(Vec<u8>, ProposalInfo<T::AccountId, BalanceOf<T>, BlockNumberFor<T>>)> {
+            Self::iter_proposals_from(start_after)
+                .filter(|(_, proposal)| (proposal.status == ProposalStatus::Open) == open)
+                .take(limit as usize)
+                .map(|(domain_name, mut proposal)| {
+                    if proposal.status == ProposalStatus::Open {
+                        (proposal.ayes, proposal.nays) = Self::tally(&domain_name);
+                    }
+                    (domain_name.into_inner(), proposal)
+                })
+                .collect()
+        }
+
+        /// The tally of a proposal, computed live while it is open.
+        pub fn proposal_tally(domain_name: Vec<u8>) -> Option<(u64, u64)> {
+            let domain_name = BoundedVec::<u8, VecSize>::try_from(domain_name).ok()?;
+            let proposal = Self::proposal_map(&domain_name)?;
+            match proposal.status {
+                ProposalStatus::Open => Some(Self::tally(&domain_name)),
+                _ => Some((proposal.ayes, proposal.nays)),
+            }
+        }
+
+        /// Lists the direct votes of `who` in proposal storage order after `start_after`.
+        pub fn account_votes(
+            who: T::AccountId,
+            start_after: Option<Vec<u8>>,
+            limit: u32,
+        ) -> Vec<(Vec<u8>, bool)> {
+            Self::iter_proposals_from(start_after)
+                .filter_map(|(domain_name, _)| {
+                    if !<VoteMap<T>>::contains_key(&domain_name, &who) {
+                        return None;
+                    }
+                    let vote = Self::vote_map(&domain_name, &who);
+                    Some((domain_name.into_inner(), vote))
+                })
+                .take(limit as usize)
+                .collect()
+        }
+
+        fn iter_proposals_from(
+            start_after: Option<Vec<u8>>,
+        ) -> impl Iterator<
+            Item = (
+                BoundedVec<u8, VecSize>,
+                ProposalInfo<T::AccountId, BalanceOf<T>, BlockNumberFor<T>>,
+            ),
+        > {
+            match start_after.and_then(|key| BoundedVec::<u8, VecSize>::try_from(key).ok()) {
+                Some(key) => <ProposalMap<T>>::iter_from(<ProposalMap<T>>::hashed_key_for(key)),
+                None => <ProposalMap<T>>::iter(),
+            }
+        }
     }
 
     /// The pallet's dispatchable functions ([`Call`]s).
@@ -182,25 +721,38 @@ pub mod pallet {
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         #[pallet::call_index(0)]
-        #[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2).ref_time())]
         pub fn propose_domain(origin: OriginFor<T>,
                               domain_name: BoundedVec<u8, VecSize>)
                               -> DispatchResult {
             // Check that the extrinsic was signed and get the signer.
             let who = ensure_signed(origin)?;
 
-            ensure!(!<ProposalMap<T>>::contains_key(&domain_name), Error::<T>::ProposalNotFound);
+            ensure!(!<ProposalMap<T>>::contains_key(&domain_name), Error::<T>::DomainAlreadyProposed);
+
+            // Reserve the bond so that flooding the proposal namespace is not free.
+            let bond = T::ProposalBond::get();
+            T::Currency::reserve(&who, bond).map_err(|_| Error::<T>::InsufficientBond)?;
 
-            <ProposalMap<T>>::insert(&domain_name, 0);
+            let end = <frame_system::Pallet<T>>::block_number() + T::VotingPeriod::get();
+            let proposal = ProposalInfo {
+                proposer: who.clone(),
+                bond,
+                ayes: 0,
+                nays: 0,
+                end,
+                status: ProposalStatus::Open,
+            };
+            <ProposalMap<T>>::insert(&domain_name, proposal);
             // Emit an event.
-            Self::deposit_event(Event::DomainProposed { domain_name, creator: who });
+            Self::deposit_event(Event::DomainProposed { domain_name, creator: who, bond });
 
             // Return a successful `DispatchResult`
             Ok(())
         }
 
         #[pallet::call_index(1)]
-        #[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2).ref_time())]
         pub fn vote_for_domain(origin: OriginFor<T>,
                                domain_name: BoundedVec<u8, VecSize>,
                                vote: bool)
@@ -208,18 +760,200 @@ pub mod pallet {
             // Check that the extrinsic was signed and get the signer.
             let voter = ensure_signed(origin)?;
 
-            ensure!(<ProposalMap<T>>::contains_key(&domain_name), Error::<T>::ProposalNotFound);
-            ensure!(!<VoteMap<T>>::contains_key(&domain_name, &voter), Error::<T>::UserAlreadyVoted);
+            Self::do_vote(voter, domain_name, vote)
+        }
 
-            <VoteMap<T>>::insert(&domain_name, &voter, &vote);
-            if vote {
-                let votes = Self::proposal_map(&domain_name).ok_or_else(|| Error::<T>::ProposalNotFound)?;
-                <ProposalMap<T>>::insert(&domain_name, votes + 1);
+        /// Closes a proposal after its voting period, returning or slashing the bond.
+        ///
+        /// Can be called by anyone once the voting period is over. `voters` must be at least the
+        /// number of direct votes cast on the proposal and bounds the weight of the tally.
+        #[pallet::call_index(2)]
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(
+            3 + (*voters as u64).saturating_mul(T::MaxDelegationDepth::get() as u64 + 3),
+            3).ref_time())]
+        pub fn close_proposal(origin: OriginFor<T>,
+                              domain_name: BoundedVec<u8, VecSize>,
+                              voters: u32)
+                              -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let mut proposal = Self::proposal_map(&domain_name).ok_or(Error::<T>::ProposalNotFound)?;
+            ensure!(proposal.status == ProposalStatus::Open, Error::<T>::ProposalNotOpen);
+            ensure!(<frame_system::Pallet<T>>::block_number() > proposal.end, Error::<T>::VotingStillOpen);
+            ensure!(Self::vote_count(&domain_name) <= voters, Error::<T>::InvalidVoterCount);
+
+            (proposal.ayes, proposal.nays) = Self::tally(&domain_name);
+            proposal.status = Self::settle_bond(&domain_name, &proposal);
+            // The rationale stays attached for the record, only its deposit is returned.
+            <ProposalMetadataMap<T>>::mutate(&domain_name, |metadata| {
+                if let Some(metadata) = metadata {
+                    T::Currency::unreserve(&proposal.proposer, metadata.deposit);
+                    metadata.deposit = Zero::zero();
+                }
+            });
+            <ProposalMap<T>>::insert(&domain_name, proposal);
+
+            Ok(())
+        }
+
+        /// Lets `to` vote on behalf of the caller on every domain proposal.
+        ///
+        /// The caller can still vote directly on any proposal, which overrides the delegate's vote
+        /// for that proposal.
+        #[pallet::call_index(3)]
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(
+            4 + (T::MaxDelegationDepth::get() as u64 + 1)
+                .saturating_mul(T::MaxDelegators::get() as u64 + 5),
+            4 + 2 * (T::MaxDelegationDepth::get() as u64 + 1)).ref_time())]
+        pub fn delegate(origin: OriginFor<T>, to: T::AccountId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(who != to, Error::<T>::SelfDelegation);
+
+            // Walk up the chain of the new delegate to rule out cycles and overly long chains.
+            let max_depth = T::MaxDelegationDepth::get();
+            let stats = Self::delegation_stats(&who);
+            let mut depth = stats.height.saturating_add(1);
+            let mut current = to.clone();
+            loop {
+                ensure!(depth <= max_depth, Error::<T>::DelegationTooDeep);
+                match Self::delegations(&current) {
+                    Some(next) => {
+                        ensure!(next != who, Error::<T>::DelegationCycle);
+                        current = next;
+                        depth += 1;
+                    },
+                    None => break,
+                }
             }
-            // Emit an event.
-            Self::deposit_event(Event::DomainVote { domain_name, voter, vote });
 
-            // Return a successful `DispatchResult`
+            if let Some(previous) = <Delegations<T>>::take(&who) {
+                <Delegators<T>>::mutate(&previous, |delegators| delegators.retain(|d| d != &who));
+                Self::detach_subtree(&previous, &stats);
+            }
+            <Delegators<T>>::try_mutate(&to, |delegators| delegators.try_push(who.clone()))
+                .map_err(|_| Error::<T>::TooManyDelegators)?;
+            <Delegations<T>>::insert(&who, &to);
+            Self::attach_subtree(&to, &stats);
+
+            Self::deposit_event(Event::VoteDelegated { delegator: who, delegate: to });
+
+            Ok(())
+        }
+
+        /// Removes the caller's delegation.
+        #[pallet::call_index(4)]
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(
+            2 + (T::MaxDelegationDepth::get() as u64 + 1)
+                .saturating_mul(T::MaxDelegators::get() as u64 + 3),
+            2 + T::MaxDelegationDepth::get() as u64 + 1).ref_time())]
+        pub fn undelegate(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let delegate = <Delegations<T>>::take(&who).ok_or(Error::<T>::NotDelegating)?;
+            <Delegators<T>>::mutate(&delegate, |delegators| delegators.retain(|d| d != &who));
+            Self::detach_subtree(&delegate, &Self::delegation_stats(&who));
+
+            Self::deposit_event(Event::VoteUndelegated { delegator: who });
+
+            Ok(())
+        }
+
+        /// Submits a batch of votes signed off-chain by their voters.
+        ///
+        /// Any account can relay the batch and pays the fee for it. Votes with a bad signature or
+        /// nonce, or that cannot be cast, are skipped and reported with `SignedVoteRejected`.
+        #[pallet::call_index(5)]
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(
+            3 * votes.len() as u64, 3 * votes.len() as u64).ref_time() +
+            votes.len() as u64 * SIGNATURE_WEIGHT)]
+        pub fn submit_signed_votes(
+            origin: OriginFor<T>,
+            votes: BoundedVec<SignedVote<T::AccountId, T::OffchainSignature>, T::MaxSignedVotes>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            for signed_vote in votes {
+                let accepted = Self::check_signed_vote(&signed_vote) &&
+                    Self::do_vote(
+                        signed_vote.voter.clone(),
+                        signed_vote.domain_name.clone(),
+                        signed_vote.vote,
+                    )
+                    .is_ok();
+
+                if accepted {
+                    // The nonce is only consumed by a vote that was cast.
+                    <VoteNonces<T>>::insert(&signed_vote.voter, signed_vote.nonce.saturating_add(1));
+                } else {
+                    Self::deposit_event(Event::SignedVoteRejected {
+                        domain_name: signed_vote.domain_name,
+                        voter: signed_vote.voter,
+                        nonce: signed_vote.nonce,
+                    });
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Attaches a rationale to an open proposal, replacing any previous one.
+        #[pallet::call_index(6)]
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 3).ref_time())]
+        pub fn set_proposal_metadata(
+            origin: OriginFor<T>,
+            domain_name: BoundedVec<u8, VecSize>,
+            source: MetadataSource<T::Hash, T::MaxMetadataLen>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let proposal = Self::proposal_map(&domain_name).ok_or(Error::<T>::ProposalNotFound)?;
+            ensure!(proposal.proposer == who, Error::<T>::NotProposer);
+            ensure!(proposal.status == ProposalStatus::Open, Error::<T>::ProposalNotOpen);
+
+            let (rationale, deposit) = match source {
+                MetadataSource::Preimage(hash) => {
+                    let len = T::Preimages::len(&hash).ok_or(Error::<T>::PreimageNotFound)?;
+                    (ProposalRationale::Preimage { hash, len }, T::MetadataDepositBase::get())
+                },
+                MetadataSource::Inline(data) => {
+                    let per_byte: BalanceOf<T> = (data.len() as u32).into();
+                    let deposit = T::MetadataDepositBase::get()
+                        .saturating_add(T::MetadataDepositPerByte::get().saturating_mul(per_byte));
+                    let hash = T::Hashing::hash(&data[..]);
+                    (ProposalRationale::Inline { hash, data }, deposit)
+                },
+            };
+
+            Self::release_metadata(&domain_name, &who);
+            T::Currency::reserve(&who, deposit).map_err(|_| Error::<T>::InsufficientBond)?;
+            if let ProposalRationale::Preimage { hash, .. } = &rationale {
+                // Keep the preimage around for as long as voters may need it.
+                T::Preimages::request(hash);
+            }
+
+            let hash = *rationale.hash();
+            <ProposalMetadataMap<T>>::insert(&domain_name, ProposalMetadata { rationale, deposit });
+            Self::deposit_event(Event::ProposalMetadataSet { domain_name, hash });
+
+            Ok(())
+        }
+
+        /// Removes the rationale of a proposal and returns its deposit.
+        #[pallet::call_index(7)]
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2).ref_time())]
+        pub fn clear_proposal_metadata(
+            origin: OriginFor<T>,
+            domain_name: BoundedVec<u8, VecSize>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let proposal = Self::proposal_map(&domain_name).ok_or(Error::<T>::ProposalNotFound)?;
+            ensure!(proposal.proposer == who, Error::<T>::NotProposer);
+            ensure!(Self::release_metadata(&domain_name, &who), Error::<T>::NoMetadata);
+
+            Self::deposit_event(Event::ProposalMetadataCleared { domain_name });
+
             Ok(())
         }
     }
@@ -0,0 +1,52 @@
+//! Storage migrations of the proposal pallet.
+
+use super::*;
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+use frame_system::pallet_prelude::BlockNumberFor;
+
+/// Replaces the vote counters of proposals with full proposal records.
+pub mod v1 {
+    use super::*;
+    use frame_support::migrations::VersionedMigration;
+    use sp_runtime::traits::Zero;
+
+    pub struct UncheckedMigrateToV1<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for UncheckedMigrateToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            // Proposals opened before bonds existed have no known proposer and nothing reserved.
+            // They are attributed to the treasury without a bond and get a full voting period.
+            let proposer = T::TreasuryAccount::get();
+            let end = <frame_system::Pallet<T>>::block_number() + T::VotingPeriod::get();
+
+            let mut translated = 0u64;
+            let mut votes_read = 0u64;
+            // The legacy counter only tracked positive votes, so count the cast votes instead.
+            ProposalMap::<T>::translate::<u64, _>(|domain_name, _| {
+                translated += 1;
+                let votes = <VoteMap<T>>::iter_prefix(&domain_name).count() as u64;
+                votes_read += votes;
+                <VoteCount<T>>::insert(&domain_name, votes.min(u32::MAX as u64) as u32);
+                Some(ProposalInfo::<_, _, BlockNumberFor<T>> {
+                    proposer: proposer.clone(),
+                    bond: Zero::zero(),
+                    ayes: 0,
+                    nays: 0,
+                    end,
+                    status: ProposalStatus::Open,
+                })
+            });
+
+            T::DbWeight::get().reads_writes(translated + votes_read + 1, 2 * translated)
+        }
+    }
+
+    /// Migrates the proposals once, when the storage version of the pallet is 0.
+    pub type MigrateToV1<T> = VersionedMigration<
+        0,
+        1,
+        UncheckedMigrateToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
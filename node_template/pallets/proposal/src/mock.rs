@@ -1,12 +1,15 @@
 use crate as pallet_proposal;
 use frame_support::{
 	derive_impl,
-	traits::{ConstU16, ConstU64},
+	parameter_types,
+	traits::{ConstU16, ConstU32, ConstU64},
 };
+use frame_system::EnsureRoot;
 use sp_core::H256;
 use sp_runtime::{
+	testing::{TestSignature, UintAuthorityId},
 	traits::{BlakeTwo256, IdentityLookup},
-	BuildStorage,
+	BuildStorage, Perbill,
 };
 
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -16,6 +19,8 @@ frame_support::construct_runtime!(
 	pub enum Test
 	{
 		System: frame_system,
+		Balances: pallet_balances,
+		Preimage: pallet_preimage,
 		ProposalModule: pallet_proposal,
 	}
 );
@@ -38,18 +43,51 @@ impl frame_system::Config for Test {
 	type BlockHashCount = ConstU64<250>;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
 	type SS58Prefix = ConstU16<42>;
 	type OnSetCode = ();
-	type MaxConsumers = frame_support::traits::ConstU32<16>;
+	type MaxConsumers = ConstU32<16>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig as pallet_balances::DefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+}
+
+impl pallet_preimage::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<u64>;
+	type Consideration = ();
+}
+
+parameter_types! {
+	pub const ApprovalThreshold: Perbill = Perbill::from_percent(50);
+	pub const SpamThreshold: Perbill = Perbill::from_percent(20);
 }
 
 impl pallet_proposal::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
+	type Currency = Balances;
+	type ProposalBond = ConstU64<100>;
+	type VotingPeriod = ConstU64<10>;
+	type ApprovalThreshold = ApprovalThreshold;
+	type SpamThreshold = SpamThreshold;
+	type TreasuryAccount = ConstU64<99>;
+	type MaxDelegationDepth = ConstU32<4>;
+	type MaxDelegators = ConstU32<16>;
+	type OffchainSignature = TestSignature;
+	type OffchainPublic = UintAuthorityId;
+	type MaxSignedVotes = ConstU32<16>;
+	type Preimages = Preimage;
+	type MetadataDepositBase = ConstU64<10>;
+	type MetadataDepositPerByte = ConstU64<1>;
+	type MaxMetadataLen = ConstU32<256>;
 }
 
 // Build genesis storage according to the mock runtime.
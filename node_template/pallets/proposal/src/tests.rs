@@ -1,27 +1,142 @@
-// use crate::{mock::*, Error, Event, DomainInfo};
-// use frame_support::{assert_noop, assert_ok};
-
-// #[test]
-// fn it_works_for_default_value() {
-// 	new_test_ext().execute_with(|| {
-// 		// Go past genesis block so events get deposited
-// 		System::set_block_number(1);
-// 		// Dispatch a signed extrinsic.
-// 		assert_ok!(TemplateModule::register_domain(RuntimeOrigin::signed(1), 42));
-// 		// Read pallet storage and assert an expected result.
-// 		assert_eq!(Something::<Test>::get(), Some(42));
-// 		// Assert that the correct event was deposited
-// 		System::assert_last_event(Event::SomethingStored { something: 42, who: 1 }.into());
-// 	});
-// }
-
-// #[test]
-// fn correct_error_for_none_value() {
-// 	new_test_ext().execute_with(|| {
-// 		// Ensure the expected error is thrown when no value is present.
-// 		assert_noop!(
-// 			TemplateModule::cause_error(RuntimeOrigin::signed(1)),
-// 			Error::<Test>::NoneValue
-// 		);
-// 	});
-// }
+use crate::{mock::*, Error, ProposalStatus};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{ConstU32, Currency, ReservableCurrency},
+	BoundedVec,
+};
+
+fn domain() -> BoundedVec<u8, ConstU32<100>> {
+	b"example.tld".to_vec().try_into().unwrap()
+}
+
+/// Opens a proposal for `domain()` by account 1.
+fn propose() {
+	System::set_block_number(1);
+	let _ = Balances::deposit_creating(&1, 1_000);
+	assert_ok!(ProposalModule::propose_domain(RuntimeOrigin::signed(1), domain()));
+}
+
+fn vote(voter: u64, aye: bool) {
+	assert_ok!(ProposalModule::vote_for_domain(RuntimeOrigin::signed(voter), domain(), aye));
+}
+
+fn delegate(delegator: u64, delegate: u64) {
+	assert_ok!(ProposalModule::delegate(RuntimeOrigin::signed(delegator), delegate));
+}
+
+#[test]
+fn direct_votes_weigh_one_each() {
+	new_test_ext().execute_with(|| {
+		propose();
+		vote(2, true);
+		vote(3, true);
+		vote(4, false);
+
+		assert_eq!(ProposalModule::tally(&domain()), (2, 1));
+		assert_noop!(
+			ProposalModule::vote_for_domain(RuntimeOrigin::signed(2), domain(), false),
+			Error::<Test>::UserAlreadyVoted
+		);
+	});
+}
+
+#[test]
+fn delegates_vote_with_their_whole_subtree() {
+	new_test_ext().execute_with(|| {
+		propose();
+		delegate(3, 2);
+		delegate(4, 3);
+		vote(2, true);
+
+		assert_eq!(ProposalModule::tally(&domain()), (3, 0));
+	});
+}
+
+#[test]
+fn direct_vote_of_a_delegator_overrides_its_delegate() {
+	new_test_ext().execute_with(|| {
+		propose();
+		delegate(3, 2);
+		delegate(4, 3);
+		vote(2, true);
+		vote(3, false);
+
+		// 3 takes its own subtree, 4 included, away from 2
+		assert_eq!(ProposalModule::tally(&domain()), (1, 2));
+	});
+}
+
+#[test]
+fn undelegating_removes_the_subtree_from_the_delegate() {
+	new_test_ext().execute_with(|| {
+		propose();
+		delegate(3, 2);
+		delegate(4, 3);
+		assert_ok!(ProposalModule::undelegate(RuntimeOrigin::signed(3)));
+		vote(2, true);
+		vote(4, false);
+
+		assert_eq!(ProposalModule::tally(&domain()), (1, 1));
+		assert_eq!(ProposalModule::delegation_stats(2).weight, 0);
+		assert_eq!(ProposalModule::delegation_stats(3).weight, 1);
+	});
+}
+
+#[test]
+fn delegation_rejects_self_cycles_and_deep_chains() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ProposalModule::delegate(RuntimeOrigin::signed(2), 2),
+			Error::<Test>::SelfDelegation
+		);
+
+		delegate(2, 3);
+		delegate(3, 4);
+		assert_noop!(
+			ProposalModule::delegate(RuntimeOrigin::signed(4), 2),
+			Error::<Test>::DelegationCycle
+		);
+
+		// `MaxDelegationDepth` is 4
+		delegate(4, 5);
+		delegate(5, 6);
+		assert_noop!(
+			ProposalModule::delegate(RuntimeOrigin::signed(6), 7),
+			Error::<Test>::DelegationTooDeep
+		);
+	});
+}
+
+#[test]
+fn closing_stores_the_tally_and_returns_the_bond() {
+	new_test_ext().execute_with(|| {
+		propose();
+		assert_eq!(Balances::reserved_balance(1), 100);
+		delegate(3, 2);
+		vote(2, true);
+		vote(4, false);
+
+		System::set_block_number(12);
+		assert_noop!(
+			ProposalModule::close_proposal(RuntimeOrigin::signed(5), domain(), 1),
+			Error::<Test>::InvalidVoterCount
+		);
+		assert_ok!(ProposalModule::close_proposal(RuntimeOrigin::signed(5), domain(), 2));
+
+		let proposal = ProposalModule::proposal_map(domain()).unwrap();
+		assert_eq!((proposal.ayes, proposal.nays), (2, 1));
+		assert_eq!(proposal.status, ProposalStatus::Approved);
+		assert_eq!(Balances::reserved_balance(1), 0);
+	});
+}
+
+#[test]
+fn closing_before_the_end_of_voting_fails() {
+	new_test_ext().execute_with(|| {
+		propose();
+		assert_noop!(
+			ProposalModule::close_proposal(RuntimeOrigin::signed(5), domain(), 0),
+			Error::<Test>::VotingStillOpen
+		);
+	});
+}
@@ -60,6 +60,8 @@ mod benchmarking;
 pub mod weights;
 pub use weights::*;
 
+pub mod migrations;
+
 // All pallet logic is defined in its own module and must be annotated by the `pallet` attribute.
 #[frame_support::pallet]
 pub mod pallet {
@@ -68,10 +70,16 @@ pub mod pallet {
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
 	use scale_info::prelude::{vec, vec::Vec};
+	use sp_consensus_grandpa::{AuthorityList, SetId};
+	use sp_core::H256;
+
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
 	// The `Pallet` struct serves as a placeholder to implement traits, methods and dispatchables
 	// (`Call`s) in this pallet.
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -88,10 +96,25 @@ pub mod pallet {
 		type WeightInfo: WeightInfo;
 	}
 
+	/// A finalized header of a TLD chain from which its GRANDPA finality can be followed.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct FinalityCheckpoint {
+		pub header_hash: H256,
+		pub number: u32,
+		pub state_root: H256,
+		// GRANDPA authorities that can finalize the descendants of this header
+		pub authorities: AuthorityList,
+		pub set_id: SetId,
+	}
+
 	#[derive(Encode, Decode, Clone, PartialEq, Default, TypeInfo)]
 	pub struct TLDInfo {
 		// A link pointing to the TLD network chain specification
 		pub chain_spec: Vec<u8>,
+		// Trusted starting point for light clients of the TLD chain
+		pub checkpoint: Option<FinalityCheckpoint>,
+		// HTTP(S) JSON-RPC endpoints of the TLD chain, preferred over its boot nodes
+		pub rpc_endpoints: Vec<Vec<u8>>,
 	}
 
 	/// A storage item for this pallet.
@@ -109,6 +132,18 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn tld_owner)]
+	pub(super) type TLDOwnerMap<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		// The TLD name
+		Vec<u8>,
+		// Account that registered the TLD
+		T::AccountId,
+		OptionQuery,
+	>;
+
 	/// Events that functions in this pallet can emit.
 	///
 	/// Events are a simple means of indicating to the outside world (such as dApps, chain explorers
@@ -123,6 +158,9 @@ pub mod pallet {
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		TLDRegistered { tld_name: Vec<u8>, creator: T::AccountId },
+		FinalityCheckpointSet { tld_name: Vec<u8>, header_hash: H256, number: u32 },
+		RpcEndpointsSet { tld_name: Vec<u8>, rpc_endpoints: Vec<Vec<u8>> },
+		TLDOwnerSet { tld_name: Vec<u8>, owner: T::AccountId },
 	}
 
 	/// Errors that can be returned by this pallet.
@@ -141,6 +179,10 @@ pub mod pallet {
 		StorageOverflow,
 		/// The domain was already registered by someone else.
 		TLDAlreadyRegistered,
+		/// Requested TLD was not found
+		TLDNotFound,
+		/// Non-owner account tried changing TLD information
+		InvalidOwnerId,
 	}
 
 	// Exposing API functions for other pallets to use
@@ -182,8 +224,9 @@ pub mod pallet {
 				return Err(Error::<T>::TLDAlreadyRegistered.into());
 			}
 
-			let tld_info = TLDInfo { chain_spec };
+			let tld_info = TLDInfo { chain_spec, checkpoint: None, rpc_endpoints: Vec::new() };
 			<TLDMap<T>>::insert(&tld_name, tld_info);
+			<TLDOwnerMap<T>>::insert(&tld_name, &who);
 
 			// Emit an event.
 			Self::deposit_event(Event::TLDRegistered { tld_name, creator: who });
@@ -191,5 +234,74 @@ pub mod pallet {
 			// Return a successful `DispatchResult`
 			Ok(())
 		}
+
+		#[pallet::call_index(1)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1).ref_time())]
+		pub fn set_finality_checkpoint(
+			origin: OriginFor<T>,
+			tld_name: Vec<u8>,
+			checkpoint: FinalityCheckpoint,
+		) -> DispatchResult {
+			// Check that the extrinsic was signed and get the signer.
+			let who = ensure_signed(origin)?;
+
+			let mut tld_info = Self::tld_map(&tld_name).ok_or(Error::<T>::TLDNotFound)?;
+			ensure!(Self::tld_owner(&tld_name) == Some(who), Error::<T>::InvalidOwnerId);
+
+			let (header_hash, number) = (checkpoint.header_hash, checkpoint.number);
+			tld_info.checkpoint = Some(checkpoint);
+			<TLDMap<T>>::insert(&tld_name, tld_info);
+
+			// Emit an event.
+			Self::deposit_event(Event::FinalityCheckpointSet { tld_name, header_hash, number });
+
+			// Return a successful `DispatchResult`
+			Ok(())
+		}
+
+		#[pallet::call_index(2)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1).ref_time())]
+		pub fn set_rpc_endpoints(
+			origin: OriginFor<T>,
+			tld_name: Vec<u8>,
+			rpc_endpoints: Vec<Vec<u8>>,
+		) -> DispatchResult {
+			// Check that the extrinsic was signed and get the signer.
+			let who = ensure_signed(origin)?;
+
+			let mut tld_info = Self::tld_map(&tld_name).ok_or(Error::<T>::TLDNotFound)?;
+			ensure!(Self::tld_owner(&tld_name) == Some(who), Error::<T>::InvalidOwnerId);
+
+			tld_info.rpc_endpoints = rpc_endpoints.clone();
+			<TLDMap<T>>::insert(&tld_name, tld_info);
+
+			// Emit an event.
+			Self::deposit_event(Event::RpcEndpointsSet { tld_name, rpc_endpoints });
+
+			// Return a successful `DispatchResult`
+			Ok(())
+		}
+
+		/// Assigns the account allowed to update the checkpoint and the RPC endpoints of a TLD.
+		///
+		/// TLDs registered before owners were recorded have none until one is set here.
+		#[pallet::call_index(3)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn set_tld_owner(
+			origin: OriginFor<T>,
+			tld_name: Vec<u8>,
+			owner: T::AccountId,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			ensure!(<TLDMap<T>>::contains_key(&tld_name), Error::<T>::TLDNotFound);
+			<TLDOwnerMap<T>>::insert(&tld_name, &owner);
+
+			// Emit an event.
+			Self::deposit_event(Event::TLDOwnerSet { tld_name, owner });
+
+			// Return a successful `DispatchResult`
+			Ok(())
+		}
 	}
 }
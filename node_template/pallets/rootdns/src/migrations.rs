@@ -0,0 +1,46 @@
+//! Storage migrations of the root DNS pallet.
+
+use super::*;
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+use scale_info::prelude::vec::Vec;
+
+/// Adds the finality checkpoint and the RPC endpoints to the TLD records.
+pub mod v1 {
+	use super::*;
+	use frame_support::migrations::VersionedMigration;
+
+	/// TLD record as stored before the checkpoint and the RPC endpoints were added.
+	#[derive(Decode)]
+	struct OldTLDInfo {
+		chain_spec: Vec<u8>,
+	}
+
+	pub struct UncheckedMigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for UncheckedMigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let mut translated = 0u64;
+			TLDMap::<T>::translate::<OldTLDInfo, _>(|_, old| {
+				translated += 1;
+				// Light clients of existing TLDs start once a checkpoint is registered for them.
+				// Their creators were never stored, so root assigns owners via `set_tld_owner`.
+				Some(TLDInfo {
+					chain_spec: old.chain_spec,
+					checkpoint: None,
+					rpc_endpoints: Vec::new(),
+				})
+			});
+
+			T::DbWeight::get().reads_writes(translated, translated)
+		}
+	}
+
+	/// Migrates the TLD records once, when the storage version of the pallet is 0.
+	pub type MigrateToV1<T> = VersionedMigration<
+		0,
+		1,
+		UncheckedMigrateToV1<T>,
+		Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+}
@@ -62,6 +62,7 @@ pub mod weights;
 pub use weights::*;
 
 pub mod crypto;
+pub mod migrations;
 
 // All pallet logic is defined in its own module and must be annotated by the `pallet` attribute.
 #[frame_support::pallet]
@@ -80,9 +81,13 @@ pub mod pallet {
 	};
 	use scale_info::prelude::{string::String, vec, vec::Vec};
 
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	// The `Pallet` struct serves as a placeholder to implement traits, methods and dispatchables
 	// (`Call`s) in this pallet.
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -98,6 +103,9 @@ pub mod pallet {
 		/// A type representing the weights required by the dispatchables of this pallet.
 		type WeightInfo: WeightInfo;
 		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+		/// Maximum number of managers a domain can have.
+		#[pallet::constant]
+		type MaxManagers: Get<u32>;
 	}
 
 	pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"bcdn");
@@ -111,6 +119,8 @@ pub mod pallet {
 		pub maintainer: Vec<u8>,
 		// Boolean signifying the availability of the domain name
 		pub available: bool,
+		// HTTP(S) JSON-RPC endpoints of the network behind the domain
+		pub rpc_endpoints: Vec<Vec<u8>>,
 	}
 
 	impl<AccountId> DomainInfo<AccountId> {
@@ -120,10 +130,20 @@ pub mod pallet {
 			maintainer: Vec<u8>,
 			available: bool,
 		) -> Self {
-			Self { creator, chain_spec, maintainer, available }
+			Self { creator, chain_spec, maintainer, available, rpc_endpoints: Vec::new() }
 		}
 	}
 
+	/// Assets provided by a domain, listed in a file published at `url`.
+	///
+	/// The file holds the SCALE encoded asset ids of the `assetdiscovery` pallet, and
+	/// `content_hash` is its Blake2-256 hash.
+	#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+	pub struct AssetManifest {
+		pub url: Vec<u8>,
+		pub content_hash: sp_core::H256,
+	}
+
 	/// A storage item for this pallet.
 	///
 	/// In this template, we are declaring a storage item called `Something` that stores a single
@@ -151,6 +171,59 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// Accounts the creator of a domain authorized to act on its behalf, such as registering the
+	/// assets it provides.
+	#[pallet::storage]
+	#[pallet::getter(fn domain_managers)]
+	pub(super) type DomainManagers<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		// Domain name
+		Vec<u8>,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
+	/// Number of managers of each domain. Domains without managers have no entry.
+	#[pallet::storage]
+	#[pallet::getter(fn manager_count)]
+	pub(super) type ManagerCount<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		// Domain name
+		Vec<u8>,
+		u32,
+		ValueQuery,
+	>;
+
+	/// Asset manifest published by each domain.
+	#[pallet::storage]
+	#[pallet::getter(fn asset_manifests)]
+	pub(super) type AssetManifests<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		// Domain name
+		Vec<u8>,
+		AssetManifest,
+		OptionQuery,
+	>;
+
+	/// Finalized header of the target chain of each domain, committed by the domain so that proofs
+	/// from its target chain can be anchored to it.
+	#[pallet::storage]
+	#[pallet::getter(fn target_headers)]
+	pub(super) type TargetHeaders<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		// Domain name
+		Vec<u8>,
+		// Header hash on the target chain
+		sp_core::H256,
+		OptionQuery,
+	>;
+
 	/// Events that functions in this pallet can emit.
 	///
 	/// Events are a simple means of indicating to the outside world (such as dApps, chain explorers
@@ -167,6 +240,11 @@ pub mod pallet {
 		DomainRegistered { domain_name: Vec<u8>, creator: T::AccountId },
 		DomainAmended { domain_name: Vec<u8>, editor: T::AccountId },
 		DomainRevoked { domain_name: Vec<u8>, revoker: T::AccountId },
+		RpcEndpointsSet { domain_name: Vec<u8>, rpc_endpoints: Vec<Vec<u8>> },
+		ManagerAdded { domain_name: Vec<u8>, manager: T::AccountId },
+		ManagerRemoved { domain_name: Vec<u8>, manager: T::AccountId },
+		AssetManifestSet { domain_name: Vec<u8>, manifest: Option<AssetManifest> },
+		TargetHeaderSet { domain_name: Vec<u8>, header_hash: Option<sp_core::H256> },
 	}
 
 	/// Errors that can be returned by this pallet.
@@ -189,6 +267,8 @@ pub mod pallet {
 		InvalidOwnerId,
 		/// Requested domain was not found
 		DomainNotFound,
+		/// The domain already has the maximum number of managers
+		TooManyManagers,
 	}
 
 	#[derive(Serialize, Deserialize, Debug)]
@@ -360,8 +440,9 @@ pub mod pallet {
 			let who = ensure_signed(origin)?;
 
 			let domain_exists = <DomainMap<T>>::contains_key(&domain_name);
+			let available = Self::domain_map(&domain_name).map_or(false, |info| info.available);
 
-			if domain_exists && !Self::domain_map(&domain_name).unwrap().available {
+			if domain_exists && !available {
 				// Return an error if the domain exists and is not available
 				return Err(Error::<T>::DomainNotAvailable.into());
 			}
@@ -388,15 +469,11 @@ pub mod pallet {
 			// Check that the extrinsic was signed and get the signer.
 			let who = ensure_signed(origin)?;
 
-			let domain_exists = <DomainMap<T>>::contains_key(&domain_name);
-
-			ensure!(domain_exists, Error::<T>::DomainNotFound);
-			ensure!(
-				who == Self::domain_map(&domain_name).unwrap().creator,
-				Error::<T>::InvalidOwnerId
-			);
+			let previous = Self::domain_map(&domain_name).ok_or(Error::<T>::DomainNotFound)?;
+			ensure!(who == previous.creator, Error::<T>::InvalidOwnerId);
 
-			let domain_info = DomainInfo::new(who.clone(), chain_spec, maintainer, false);
+			let mut domain_info = DomainInfo::new(who.clone(), chain_spec, maintainer, false);
+			domain_info.rpc_endpoints = previous.rpc_endpoints;
 			<DomainMap<T>>::insert(&domain_name, &domain_info);
 			<MaintainerMap<T>>::insert(&domain_info.maintainer, &domain_name);
 
@@ -408,21 +485,24 @@ pub mod pallet {
 		}
 
 		#[pallet::call_index(2)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(
+			1,
+			4 + T::MaxManagers::get() as u64,
+		).ref_time())]
 		pub fn revoke_domain(origin: OriginFor<T>, domain_name: Vec<u8>) -> DispatchResult {
 			// Check that the extrinsic was signed and get the signer.
 			let who = ensure_signed(origin)?;
 
-			let domain_exists = <DomainMap<T>>::contains_key(&domain_name);
-
-			ensure!(domain_exists, Error::<T>::DomainNotFound);
-			ensure!(
-				who == Self::domain_map(&domain_name).unwrap().creator,
-				Error::<T>::InvalidOwnerId
-			);
+			let previous = Self::domain_map(&domain_name).ok_or(Error::<T>::DomainNotFound)?;
+			ensure!(who == previous.creator, Error::<T>::InvalidOwnerId);
 
 			let domain_info = DomainInfo::new(who.clone(), Vec::new(), Vec::new(), true);
 			<DomainMap<T>>::insert(&domain_name, domain_info);
+			// Managers must not carry over to whoever registers the domain next
+			let _ = <DomainManagers<T>>::clear_prefix(&domain_name, T::MaxManagers::get(), None);
+			<ManagerCount<T>>::remove(&domain_name);
+			<AssetManifests<T>>::remove(&domain_name);
+			<TargetHeaders<T>>::remove(&domain_name);
 
 			// Emit an event.
 			Self::deposit_event(Event::DomainRevoked { domain_name, revoker: who });
@@ -430,5 +510,147 @@ pub mod pallet {
 			// Return a successful `DispatchResult`
 			Ok(())
 		}
+
+		#[pallet::call_index(3)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn set_rpc_endpoints(
+			origin: OriginFor<T>,
+			domain_name: Vec<u8>,
+			rpc_endpoints: Vec<Vec<u8>>,
+		) -> DispatchResult {
+			// Check that the extrinsic was signed and get the signer.
+			let who = ensure_signed(origin)?;
+
+			let mut domain_info =
+				Self::domain_map(&domain_name).ok_or(Error::<T>::DomainNotFound)?;
+			ensure!(!domain_info.available, Error::<T>::DomainNotFound);
+			ensure!(who == domain_info.creator, Error::<T>::InvalidOwnerId);
+
+			domain_info.rpc_endpoints = rpc_endpoints.clone();
+			<DomainMap<T>>::insert(&domain_name, domain_info);
+
+			// Emit an event.
+			Self::deposit_event(Event::RpcEndpointsSet { domain_name, rpc_endpoints });
+
+			// Return a successful `DispatchResult`
+			Ok(())
+		}
+
+		#[pallet::call_index(4)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 2).ref_time())]
+		pub fn add_manager(
+			origin: OriginFor<T>,
+			domain_name: Vec<u8>,
+			manager: T::AccountId,
+		) -> DispatchResult {
+			// Check that the extrinsic was signed and get the signer.
+			let who = ensure_signed(origin)?;
+			Self::ensure_creator(&who, &domain_name)?;
+
+			if !<DomainManagers<T>>::contains_key(&domain_name, &manager) {
+				let count = Self::manager_count(&domain_name);
+				ensure!(count < T::MaxManagers::get(), Error::<T>::TooManyManagers);
+				<ManagerCount<T>>::insert(&domain_name, count + 1);
+				<DomainManagers<T>>::insert(&domain_name, &manager, ());
+			}
+
+			// Emit an event.
+			Self::deposit_event(Event::ManagerAdded { domain_name, manager });
+
+			// Return a successful `DispatchResult`
+			Ok(())
+		}
+
+		#[pallet::call_index(5)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 2).ref_time())]
+		pub fn remove_manager(
+			origin: OriginFor<T>,
+			domain_name: Vec<u8>,
+			manager: T::AccountId,
+		) -> DispatchResult {
+			// Check that the extrinsic was signed and get the signer.
+			let who = ensure_signed(origin)?;
+			Self::ensure_creator(&who, &domain_name)?;
+
+			if <DomainManagers<T>>::take(&domain_name, &manager).is_some() {
+				<ManagerCount<T>>::mutate_exists(&domain_name, |count| {
+					*count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0)
+				});
+			}
+
+			// Emit an event.
+			Self::deposit_event(Event::ManagerRemoved { domain_name, manager });
+
+			// Return a successful `DispatchResult`
+			Ok(())
+		}
+
+		#[pallet::call_index(6)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1).ref_time())]
+		pub fn set_asset_manifest(
+			origin: OriginFor<T>,
+			domain_name: Vec<u8>,
+			manifest: Option<AssetManifest>,
+		) -> DispatchResult {
+			// Check that the extrinsic was signed and get the signer.
+			let who = ensure_signed(origin)?;
+			if Self::ensure_creator(&who, &domain_name).is_err() {
+				ensure!(
+					<DomainManagers<T>>::contains_key(&domain_name, &who),
+					Error::<T>::InvalidOwnerId
+				);
+			}
+
+			match &manifest {
+				Some(manifest) => <AssetManifests<T>>::insert(&domain_name, manifest),
+				None => <AssetManifests<T>>::remove(&domain_name),
+			}
+
+			// Emit an event.
+			Self::deposit_event(Event::AssetManifestSet { domain_name, manifest });
+
+			// Return a successful `DispatchResult`
+			Ok(())
+		}
+
+		/// Commits a finalized header of the target chain of the domain, at which the assets it
+		/// provides are proven.
+		#[pallet::call_index(7)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1).ref_time())]
+		pub fn set_target_header(
+			origin: OriginFor<T>,
+			domain_name: Vec<u8>,
+			header_hash: Option<sp_core::H256>,
+		) -> DispatchResult {
+			// Check that the extrinsic was signed and get the signer.
+			let who = ensure_signed(origin)?;
+			if Self::ensure_creator(&who, &domain_name).is_err() {
+				ensure!(
+					<DomainManagers<T>>::contains_key(&domain_name, &who),
+					Error::<T>::InvalidOwnerId
+				);
+			}
+
+			match header_hash {
+				Some(header_hash) => <TargetHeaders<T>>::insert(&domain_name, header_hash),
+				None => <TargetHeaders<T>>::remove(&domain_name),
+			}
+
+			// Emit an event.
+			Self::deposit_event(Event::TargetHeaderSet { domain_name, header_hash });
+
+			// Return a successful `DispatchResult`
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Checks that `who` created the registered domain `domain_name`.
+		fn ensure_creator(who: &T::AccountId, domain_name: &[u8]) -> DispatchResult {
+			let domain_info = Self::domain_map(domain_name).ok_or(Error::<T>::DomainNotFound)?;
+			ensure!(!domain_info.available, Error::<T>::DomainNotFound);
+			ensure!(who == &domain_info.creator, Error::<T>::InvalidOwnerId);
+			Ok(())
+		}
 	}
 }
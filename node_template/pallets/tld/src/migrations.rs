@@ -0,0 +1,50 @@
+//! Storage migrations of the TLD pallet.
+
+use super::*;
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+use scale_info::prelude::vec::Vec;
+
+/// Adds the RPC endpoints to the domain records.
+pub mod v1 {
+	use super::*;
+	use frame_support::migrations::VersionedMigration;
+
+	/// Domain record as stored before the RPC endpoints were added.
+	#[derive(Decode)]
+	struct OldDomainInfo<AccountId> {
+		creator: AccountId,
+		chain_spec: Vec<u8>,
+		maintainer: Vec<u8>,
+		available: bool,
+	}
+
+	pub struct UncheckedMigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for UncheckedMigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let mut translated = 0u64;
+			DomainMap::<T>::translate::<OldDomainInfo<T::AccountId>, _>(|_, old| {
+				translated += 1;
+				Some(DomainInfo {
+					creator: old.creator,
+					chain_spec: old.chain_spec,
+					maintainer: old.maintainer,
+					available: old.available,
+					rpc_endpoints: Vec::new(),
+				})
+			});
+			log::info!("Migrated {} domain records to v1", translated);
+
+			T::DbWeight::get().reads_writes(translated, translated)
+		}
+	}
+
+	/// Migrates the domain records once, when the storage version of the pallet is 0.
+	pub type MigrateToV1<T> = VersionedMigration<
+		0,
+		1,
+		UncheckedMigrateToV1<T>,
+		Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+}
@@ -10,7 +10,10 @@ use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
-	traits::{BlakeTwo256, Block as BlockT, Extrinsic, IdentifyAccount, NumberFor, One, Verify},
+	traits::{
+		AccountIdConversion, BlakeTwo256, Block as BlockT, Extrinsic, IdentifyAccount, NumberFor,
+		One, Verify,
+	},
 	transaction_validity::{TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, MultiSignature, SaturatedConversion,
 };
@@ -23,8 +26,8 @@ use frame_support::genesis_builder_helper::{build_config, create_default_config}
 pub use frame_support::{
 	construct_runtime, derive_impl, parameter_types,
 	traits::{
-		ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness,
-		StorageInfo,
+		fungible::HoldConsideration, ConstBool, ConstU128, ConstU32, ConstU64, ConstU8,
+		KeyOwnerProofSystem, LinearStoragePrice, Randomness, StorageInfo,
 	},
 	weights::{
 		constants::{
@@ -32,7 +35,7 @@ pub use frame_support::{
 		},
 		IdentityFee, Weight,
 	},
-	StorageValue,
+	PalletId, StorageValue,
 };
 pub use frame_system::Call as SystemCall;
 use frame_system::{EnsureRoot, EnsureSigned};
@@ -136,6 +139,12 @@ pub const STRING_LIMIT: u32 = 50;
 pub const METADATA_DEPOSIT_BASE: Balance = 10;
 pub const METADATA_DEPOSIT_PER_BYTE: Balance = 1;
 pub const REMOVE_ITEMS_LIMIT: u32 = 50;
+pub const PROPOSAL_BOND: Balance = 1_000;
+pub const PROPOSAL_METADATA_DEPOSIT_BASE: Balance = 10;
+pub const PROPOSAL_METADATA_DEPOSIT_PER_BYTE: Balance = 1;
+pub const PREIMAGE_BASE_DEPOSIT: Balance = 10;
+pub const PREIMAGE_BYTE_DEPOSIT: Balance = 1;
+pub const ASSET_REQUEST_DEPOSIT: Balance = 100;
 
 /// The version information used to identify this runtime when compiled natively.
 #[cfg(feature = "std")]
@@ -236,7 +245,7 @@ impl pallet_balances::Config for Runtime {
 	type WeightInfo = pallet_balances::weights::SubstrateWeight<Runtime>;
 	type FreezeIdentifier = ();
 	type MaxFreezes = ();
-	type RuntimeHoldReason = ();
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type RuntimeFreezeReason = ();
 }
 
@@ -269,11 +278,58 @@ impl pallet_tld::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = pallet_tld::weights::SubstrateWeight<Runtime>;
 	type AuthorityId = pallet_tld::crypto::TestAuthId;
+	type MaxManagers = ConstU32<16>;
+}
+
+parameter_types! {
+	pub const ProposalBond: Balance = PROPOSAL_BOND;
+	pub const ProposalVotingPeriod: BlockNumber = 7 * DAYS;
+	pub const ProposalApprovalThreshold: Perbill = Perbill::from_percent(50);
+	pub const ProposalSpamThreshold: Perbill = Perbill::from_percent(10);
+	pub const TreasuryPalletId: PalletId = PalletId(*b"bcdn/try");
+	pub TreasuryAccount: AccountId = TreasuryPalletId::get().into_account_truncating();
+	pub const ProposalMetadataDepositBase: Balance = PROPOSAL_METADATA_DEPOSIT_BASE;
+	pub const ProposalMetadataDepositPerByte: Balance = PROPOSAL_METADATA_DEPOSIT_PER_BYTE;
 }
 
 impl pallet_proposal::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = pallet_proposal::weights::SubstrateWeight<Runtime>;
+	type Currency = Balances;
+	type ProposalBond = ProposalBond;
+	type VotingPeriod = ProposalVotingPeriod;
+	type ApprovalThreshold = ProposalApprovalThreshold;
+	type SpamThreshold = ProposalSpamThreshold;
+	type TreasuryAccount = TreasuryAccount;
+	type MaxDelegationDepth = ConstU32<8>;
+	type MaxDelegators = ConstU32<128>;
+	type OffchainSignature = Signature;
+	type OffchainPublic = <Signature as Verify>::Signer;
+	type MaxSignedVotes = ConstU32<256>;
+	type Preimages = Preimage;
+	type MetadataDepositBase = ProposalMetadataDepositBase;
+	type MetadataDepositPerByte = ProposalMetadataDepositPerByte;
+	type MaxMetadataLen = ConstU32<4096>;
+}
+
+parameter_types! {
+	pub const PreimageBaseDeposit: Balance = PREIMAGE_BASE_DEPOSIT;
+	pub const PreimageByteDeposit: Balance = PREIMAGE_BYTE_DEPOSIT;
+	pub const PreimageHoldReason: RuntimeHoldReason =
+		RuntimeHoldReason::Preimage(pallet_preimage::HoldReason::Preimage);
+}
+
+impl pallet_preimage::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<AccountId>;
+	type Consideration = HoldConsideration<
+		AccountId,
+		Balances,
+		PreimageHoldReason,
+		LinearStoragePrice<PreimageBaseDeposit, PreimageByteDeposit, Balance>,
+	>;
 }
 
 impl pallet_rootdns::Config for Runtime {
@@ -281,10 +337,51 @@ impl pallet_rootdns::Config for Runtime {
 	type WeightInfo = pallet_rootdns::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	pub TldModulePrefix: Vec<u8> = b"TldModule".to_vec();
+	pub TldStoragePrefix: Vec<u8> = b"DomainMap".to_vec();
+	pub AssetsModulePrefix: Vec<u8> = b"AssetsModule".to_vec();
+	pub const AssetRequestDeposit: Balance = ASSET_REQUEST_DEPOSIT;
+	pub const AssetRequestSlash: Perbill = Perbill::from_percent(50);
+	pub const AssetManifestDeposit: Balance = ASSET_REQUEST_DEPOSIT;
+}
+
 impl pallet_assetdiscovery::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = pallet_assetdiscovery::weights::SubstrateWeight<Runtime>;
 	type PalletRootDNS = Runtime;
+	type PalletLightClient = Runtime;
+	type AuthorityId = pallet_assetdiscovery::crypto::AuthorityId;
+	type AuthorityOrigin = EnsureRoot<AccountId>;
+	type ParametersOrigin = EnsureRoot<AccountId>;
+	type DomainBatchSize = ConstU32<10>;
+	type RequestLifetime = ConstU32<1000>;
+	type OcwCadence = ConstU32<10>;
+	type HttpTimeoutMs = ConstU64<5000>;
+	type RevalidationWindow = ConstU32<1000>;
+	type AttestationThreshold = ConstU32<2>;
+	type AttestationWindow = ConstU32<HOURS>;
+	type MaxAuthorities = ConstU32<16>;
+	type TldModulePrefix = TldModulePrefix;
+	type TldStoragePrefix = TldStoragePrefix;
+	type AssetsModulePrefix = AssetsModulePrefix;
+	type TldQueryQuorum = ConstU32<1>;
+	type MaxProofAge = ConstU32<{ 10 * MINUTES }>;
+	type OwnerSignature = Signature;
+	type OwnerPublic = <Signature as Verify>::Signer;
+	type Currency = Balances;
+	type RequestDeposit = AssetRequestDeposit;
+	type RequestSlash = AssetRequestSlash;
+	type MaxPendingRequests = ConstU32<16>;
+	type ManifestDeposit = AssetManifestDeposit;
+	type TreasuryAccount = TreasuryAccount;
+}
+
+impl pallet_lightclient::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type PalletRootDNS = Runtime;
+	type HeadersToKeep = ConstU32<{ DAYS }>;
+	type WeightInfo = pallet_lightclient::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -428,6 +525,12 @@ mod runtime {
 
 	#[runtime::pallet_index(12)]
 	pub type AssetDiscoveryModule = pallet_assetdiscovery;
+
+	#[runtime::pallet_index(13)]
+	pub type Preimage = pallet_preimage;
+
+	#[runtime::pallet_index(14)]
+	pub type LightClientModule = pallet_lightclient;
 }
 
 /// The address format for describing accounts.
@@ -452,7 +555,12 @@ pub type SignedExtra = (
 ///
 /// This can be a tuple of types, each implementing `OnRuntimeUpgrade`.
 #[allow(unused_parens)]
-type Migrations = ();
+type Migrations = (
+	pallet_tld::migrations::v1::MigrateToV1<Runtime>,
+	pallet_rootdns::migrations::v1::MigrateToV1<Runtime>,
+	pallet_proposal::migrations::v1::MigrateToV1<Runtime>,
+	pallet_assetdiscovery::migrations::v1::MigrateToV1<Runtime>,
+);
 
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic =
@@ -482,6 +590,8 @@ mod benches {
 		[pallet_rootdns, RootDNSModule]
 		[pallet_proposal, ProposalModule]
 		[pallet_assetdiscovery, AssetDiscoveryModule]
+		[pallet_lightclient, LightClientModule]
+		[pallet_preimage, Preimage]
 	);
 }
 
@@ -609,6 +719,58 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_proposal_runtime_api::ProposalApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+		fn open_proposals(
+			start_after: Option<Vec<u8>>,
+			limit: u32,
+		) -> Vec<(Vec<u8>, pallet_proposal::ProposalInfo<AccountId, Balance, BlockNumber>)> {
+			ProposalModule::proposals(true, start_after, limit)
+		}
+
+		fn finished_proposals(
+			start_after: Option<Vec<u8>>,
+			limit: u32,
+		) -> Vec<(Vec<u8>, pallet_proposal::ProposalInfo<AccountId, Balance, BlockNumber>)> {
+			ProposalModule::proposals(false, start_after, limit)
+		}
+
+		fn proposal_tally(domain_name: Vec<u8>) -> Option<(u64, u64)> {
+			ProposalModule::proposal_tally(domain_name)
+		}
+
+		fn account_votes(who: AccountId, start_after: Option<Vec<u8>>, limit: u32) -> Vec<(Vec<u8>, bool)> {
+			ProposalModule::account_votes(who, start_after, limit)
+		}
+	}
+
+	impl pallet_assetdiscovery_runtime_api::AssetDiscoveryApi<Block, AccountId, BlockNumber> for Runtime {
+		fn providers_for_asset(asset_hash: Vec<u8>) -> Vec<Vec<u8>> {
+			AssetDiscoveryModule::providers_of(&asset_hash)
+		}
+
+		fn assets_for_domain(domain: Vec<u8>) -> Vec<Vec<u8>> {
+			AssetDiscoveryModule::assets_of(&domain)
+		}
+
+		fn asset_metadata(
+			asset_hash: Vec<u8>,
+			domain: Vec<u8>,
+		) -> Option<pallet_assetdiscovery::AssetInfo<AccountId, BlockNumber>> {
+			AssetDiscoveryModule::asset_metadata(asset_hash, domain)
+		}
+
+		fn search_assets(
+			name_prefix: Vec<u8>,
+			start_after: Option<(Vec<u8>, Vec<u8>)>,
+			limit: u32,
+		) -> (
+			Vec<(Vec<u8>, Vec<u8>, pallet_assetdiscovery::AssetInfo<AccountId, BlockNumber>)>,
+			Option<(Vec<u8>, Vec<u8>)>,
+		) {
+			AssetDiscoveryModule::search_assets(&name_prefix, start_after, limit)
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance> for Runtime {
 		fn query_info(
 			uxt: <Block as BlockT>::Extrinsic,
@@ -15,6 +15,14 @@
   --password $PASSWORD \
   --key-type gran
 
+/substrate/target/release/node-template key insert \
+  --base-path /tmp/$NODE_NAME \
+  --chain customSpecRaw.json \
+  --scheme Sr25519 \
+  --suri "$SECRET_PHRASE" \
+  --password $PASSWORD \
+  --key-type bcda
+
 # 12D3KooWEyoppNCUx8Yx66oV9fJnriXwCcXwDDUA2kj6vnc6iDEp
 
 if [ $NORMAL_NODE ]